@@ -7,7 +7,43 @@ pub struct AST {
 
 #[derive(Debug)]
 pub enum Statement {
-    FunctionDefinition(FunctionDefinition),
+    FunctionDefinition(Box<FunctionDefinition>),
+    RecordDefinition(RecordDefinition),
+    Import(Import),
+    ConstantDefinition(Box<ConstantDefinition>),
+}
+
+/// Names a value computed once before `main` runs, visible from every
+/// function.
+#[derive(Debug)]
+pub struct ConstantDefinition {
+    pub variable: VariableDefinition,
+    pub value: Expression,
+    pub location: Location,
+}
+
+/// Makes the functions and records of another file available, qualified by
+/// the name of the file, as in `math.square(2)`.
+#[derive(Debug)]
+pub struct Import {
+    /// The path of the imported file, relative to the importing one.
+    pub path: String,
+    pub location: Location,
+}
+
+/// Declares a record type, whose values hold a value for each of its fields.
+#[derive(Debug)]
+pub struct RecordDefinition {
+    pub name: String,
+    pub fields: Vec<FieldDefinition>,
+    pub location: Location,
+}
+
+#[derive(Debug)]
+pub struct FieldDefinition {
+    pub name: String,
+    pub r#type: TypeAnnotation,
+    pub location: Location,
 }
 
 #[derive(Debug)]
@@ -21,31 +57,283 @@ pub struct FunctionDefinition {
 pub struct FunctionPrototype {
     pub name: String,
     pub arguments: Vec<VariableDefinition>,
+    pub return_type: Option<TypeAnnotation>,
     pub location: Location,
 }
 
 #[derive(Debug)]
 pub struct VariableDefinition {
+    pub name: String,
+    pub r#type: Option<TypeAnnotation>,
+    pub location: Location,
+}
+
+#[derive(Debug)]
+pub enum TypeAnnotation {
+    Named(NamedType),
+    List(ListType),
+}
+
+impl TypeAnnotation {
+    pub fn location(&self) -> &Location {
+        match self {
+            TypeAnnotation::Named(named) => &named.location,
+            TypeAnnotation::List(list) => &list.location,
+        }
+    }
+}
+
+/// A type referred to by its name, such as `Int`.
+#[derive(Debug)]
+pub struct NamedType {
     pub name: String,
     pub location: Location,
 }
 
+/// The type of lists holding elements of a given type, written `[Int]`.
+#[derive(Debug)]
+pub struct ListType {
+    pub element: Box<TypeAnnotation>,
+    pub location: Location,
+}
+
 #[derive(Debug)]
 pub enum Expression {
+    Block(Block),
     FunctionCall(FunctionCall),
     Branch(Branch),
+    Match(Match),
+    Let(Let),
+    List(List),
+    Record(Record),
+    FieldAccess(FieldAccess),
+    Lambda(Lambda),
     Variable(Variable),
     Literal(Literal),
     Empty,
 }
 
+impl Expression {
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            Expression::Block(block) => Some(&block.location),
+            Expression::FunctionCall(call) => Some(&call.location),
+            Expression::Branch(branch) => Some(&branch.location),
+            Expression::Match(r#match) => Some(&r#match.location),
+            Expression::Let(binding) => Some(&binding.location),
+            Expression::List(list) => Some(&list.location),
+            Expression::Record(record) => Some(&record.location),
+            Expression::FieldAccess(access) => Some(&access.location),
+            Expression::Lambda(lambda) => Some(&lambda.location),
+            Expression::Variable(variable) => Some(&variable.location),
+            Expression::Literal(literal) => Some(literal.location()),
+            Expression::Empty => None,
+        }
+    }
+
+    /// Collects the names an expression refers to which are not bound within
+    /// it: functions it calls and variables, constants or functions it uses.
+    pub fn references<'a>(&'a self, bound: &mut Vec<&'a str>, names: &mut Vec<&'a str>) {
+        match self {
+            Expression::FunctionCall(call) => {
+                if call.operator.is_none() && !bound.contains(&call.name.as_str()) {
+                    names.push(&call.name);
+                }
+                for parameter in &call.parameters {
+                    parameter.references(bound, names);
+                }
+            }
+            Expression::Variable(variable) => {
+                if !bound.contains(&variable.name.as_str()) {
+                    names.push(&variable.name);
+                }
+            }
+            Expression::Block(block) => {
+                let outer = bound.len();
+                for expression in &block.expressions {
+                    expression.references(bound, names);
+                }
+                bound.truncate(outer);
+            }
+            Expression::Let(binding) => {
+                binding.value.references(bound, names);
+                bound.push(&binding.variable.name);
+            }
+            Expression::Lambda(lambda) => {
+                let outer = bound.len();
+                bound.extend(
+                    lambda
+                        .arguments
+                        .iter()
+                        .map(|argument| argument.name.as_str()),
+                );
+                lambda.body.references(bound, names);
+                bound.truncate(outer);
+            }
+            Expression::Branch(branch) => {
+                branch.condition.references(bound, names);
+                branch.then.references(bound, names);
+                branch.r#else.references(bound, names);
+            }
+            Expression::Match(r#match) => {
+                r#match.value.references(bound, names);
+                for arm in &r#match.arms {
+                    arm.body.references(bound, names);
+                }
+            }
+            Expression::List(list) => {
+                for element in &list.elements {
+                    element.references(bound, names);
+                }
+            }
+            Expression::Record(record) => {
+                for field in &record.fields {
+                    field.value.references(bound, names);
+                }
+            }
+            Expression::FieldAccess(access) => access.record.references(bound, names),
+            Expression::Literal(_) | Expression::Empty => {}
+        }
+    }
+}
+
+/// A sequence of expressions, the last one giving the block its value.
+#[derive(Debug)]
+pub struct Block {
+    pub expressions: Vec<Expression>,
+    pub location: Location,
+}
+
 #[derive(Debug)]
 pub struct FunctionCall {
     pub name: String,
     pub parameters: Vec<Expression>,
+    /// The operator this call was written with, if any, such as `+` for
+    /// `add`.
+    pub operator: Option<Operator>,
     pub location: Location,
 }
 
+impl FunctionCall {
+    /// Looks up what the call refers to, operators always standing for
+    /// native functions.
+    pub fn callee<V, F>(
+        &self,
+        value: impl FnOnce() -> Option<V>,
+        function: impl FnOnce() -> Option<F>,
+    ) -> Callee<V, F> {
+        match self.operator {
+            Some(_) => Callee::Native,
+            None => Callee::lookup(value, function),
+        }
+    }
+}
+
+/// What a name which is called or used as a value refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Callee<V, F> {
+    /// A variable or a constant, which may hold a function.
+    Value(V),
+    /// A function of the program.
+    Function(F),
+    /// A native function, if any has this name.
+    Native,
+}
+
+impl<V, F> Callee<V, F> {
+    /// Variables and constants take precedence over functions of the
+    /// program, which take precedence over native ones.
+    pub fn lookup(value: impl FnOnce() -> Option<V>, function: impl FnOnce() -> Option<F>) -> Self {
+        if let Some(value) = value() {
+            Callee::Value(value)
+        } else if let Some(function) = function() {
+            Callee::Function(function)
+        } else {
+            Callee::Native
+        }
+    }
+}
+
+/// Operators are syntactic sugar for calls to native functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Or,
+    And,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Add,
+    Sub,
+    Multiply,
+    Divide,
+    Modulo,
+    Not,
+    Negate,
+}
+
+impl Operator {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Operator::Or => "||",
+            Operator::And => "&&",
+            Operator::Equal => "==",
+            Operator::NotEqual => "!=",
+            Operator::Less => "<",
+            Operator::LessEqual => "<=",
+            Operator::Greater => ">",
+            Operator::GreaterEqual => ">=",
+            Operator::Add => "+",
+            Operator::Sub | Operator::Negate => "-",
+            Operator::Multiply => "*",
+            Operator::Divide => "/",
+            Operator::Modulo => "%",
+            Operator::Not => "!",
+        }
+    }
+
+    /// The native function the operator stands for.
+    pub fn function(&self) -> &'static str {
+        match self {
+            Operator::Or => "or",
+            Operator::And => "and",
+            Operator::Equal => "equal",
+            Operator::NotEqual => "not_equal",
+            Operator::Less => "less",
+            Operator::LessEqual => "less_equal",
+            Operator::Greater => "greater",
+            Operator::GreaterEqual => "greater_equal",
+            Operator::Add => "add",
+            Operator::Sub => "sub",
+            Operator::Multiply => "multiply",
+            Operator::Divide => "divide",
+            Operator::Modulo => "modulo",
+            Operator::Not => "not",
+            Operator::Negate => "negate",
+        }
+    }
+
+    /// How tightly the operator binds its operands, binary operators all
+    /// being left associative.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            Operator::Or => 1,
+            Operator::And => 2,
+            Operator::Equal | Operator::NotEqual => 3,
+            Operator::Less | Operator::LessEqual | Operator::Greater | Operator::GreaterEqual => 4,
+            Operator::Add | Operator::Sub => 5,
+            Operator::Multiply | Operator::Divide | Operator::Modulo => 6,
+            Operator::Not | Operator::Negate => 7,
+        }
+    }
+
+    pub fn is_unary(&self) -> bool {
+        matches!(self, Operator::Not | Operator::Negate)
+    }
+}
+
 #[derive(Debug)]
 pub struct Branch {
     pub condition: Box<Expression>,
@@ -54,6 +342,81 @@ pub struct Branch {
     pub location: Location,
 }
 
+/// Evaluates the body of the first arm whose pattern matches a value.
+#[derive(Debug)]
+pub struct Match {
+    pub value: Box<Expression>,
+    pub arms: Vec<Arm>,
+    pub location: Location,
+}
+
+#[derive(Debug)]
+pub struct Arm {
+    pub pattern: Pattern,
+    pub body: Expression,
+    pub location: Location,
+}
+
+#[derive(Debug)]
+pub enum Pattern {
+    Literal(Literal),
+    /// `_`, matching any value.
+    Wildcard(Location),
+}
+
+impl Pattern {
+    pub fn location(&self) -> &Location {
+        match self {
+            Pattern::Literal(literal) => literal.location(),
+            Pattern::Wildcard(location) => location,
+        }
+    }
+}
+
+/// Binds a value to a name for the rest of the enclosing block.
+#[derive(Debug)]
+pub struct Let {
+    pub variable: VariableDefinition,
+    pub value: Box<Expression>,
+    pub location: Location,
+}
+
+#[derive(Debug)]
+pub struct List {
+    pub elements: Vec<Expression>,
+    pub location: Location,
+}
+
+/// A record value, whose type is the declared record having the same fields.
+#[derive(Debug)]
+pub struct Record {
+    pub fields: Vec<FieldValue>,
+    pub location: Location,
+}
+
+#[derive(Debug)]
+pub struct FieldValue {
+    pub name: String,
+    pub value: Expression,
+    pub location: Location,
+}
+
+#[derive(Debug)]
+pub struct FieldAccess {
+    pub record: Box<Expression>,
+    pub field: String,
+    pub location: Location,
+}
+
+/// An anonymous function, capturing the variables of the scope it is
+/// evaluated in.
+#[derive(Debug)]
+pub struct Lambda {
+    pub arguments: Vec<VariableDefinition>,
+    pub body: Box<Expression>,
+    pub location: Location,
+}
+
 #[derive(Debug)]
 pub struct Variable {
     pub name: String,
@@ -62,11 +425,45 @@ pub struct Variable {
 
 #[derive(Debug)]
 pub enum Literal {
+    Bool(Bool),
     Number(Number),
+    Float(Float),
+    String(StringLiteral),
+}
+
+impl Literal {
+    pub fn location(&self) -> &Location {
+        match self {
+            Literal::Bool(boolean) => &boolean.location,
+            Literal::Number(number) => &number.location,
+            Literal::Float(float) => &float.location,
+            Literal::String(string) => &string.location,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Bool {
+    pub value: bool,
+    pub location: Location,
 }
 
 #[derive(Debug)]
 pub struct Number {
-    pub value: u32,
+    pub value: i64,
+    pub location: Location,
+}
+
+#[derive(Debug)]
+pub struct Float {
+    pub value: f64,
+    /// The literal as written, which the formatter gives back unchanged.
+    pub raw: String,
+    pub location: Location,
+}
+
+#[derive(Debug)]
+pub struct StringLiteral {
+    pub value: String,
     pub location: Location,
 }
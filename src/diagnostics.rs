@@ -0,0 +1,217 @@
+use std::error::Error;
+
+use super::lexing::Location;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Note,
+    Warning,
+    Error,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Severity::Note => write!(f, "note"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Stable identifiers for every kind of diagnostic the toolchain can emit.
+///
+/// Codes must never be renumbered: tooling relies on them to filter and
+/// suppress findings across releases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Code {
+    Io,
+    FileExtension,
+    Tracing,
+    UnexpectedCharacter,
+    InvalidEscape,
+    UnterminatedString,
+    UnexpectedToken,
+    UnexpectedEndOfFile,
+    NumberOutOfRange,
+    UnknownFunction,
+    UnknownVariable,
+    ArgumentsCount,
+    DuplicatedArgument,
+    EmptyCondition,
+    UnknownType,
+    UnknownRecord,
+    UnknownField,
+    DuplicatedField,
+    CyclicConstant,
+    ImpureConstant,
+    AmbiguousRecord,
+    UnusedVariable,
+    UnusedFunction,
+    UnusedValue,
+    ShadowedVariable,
+    NonExhaustiveMatch,
+    UnreachablePattern,
+    TypeMismatch,
+    NoMatchingSignature,
+    NonBooleanCondition,
+    MissingMain,
+    MissingValue,
+    IndexOutOfBounds,
+    EmptyList,
+    IntegerOverflow,
+    InvalidConversion,
+    DivisionByZero,
+    NoMatchingArm,
+    EndOfInput,
+    ImportCycle,
+    UnknownModule,
+    ConflictingModules,
+}
+
+impl Code {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Code::Io => "E0001",
+            Code::FileExtension => "E0002",
+            Code::Tracing => "E0003",
+            Code::UnexpectedCharacter => "E0100",
+            Code::InvalidEscape => "E0101",
+            Code::UnterminatedString => "E0102",
+            Code::UnexpectedToken => "E0200",
+            Code::UnexpectedEndOfFile => "E0201",
+            Code::NumberOutOfRange => "E0202",
+            Code::UnknownFunction => "E0300",
+            Code::UnknownVariable => "E0301",
+            Code::ArgumentsCount => "E0302",
+            Code::DuplicatedArgument => "E0303",
+            Code::EmptyCondition => "E0304",
+            Code::UnknownType => "E0305",
+            Code::UnknownRecord => "E0306",
+            Code::UnknownField => "E0307",
+            Code::DuplicatedField => "E0308",
+            Code::CyclicConstant => "E0309",
+            Code::ImpureConstant => "E0310",
+            Code::AmbiguousRecord => "E0311",
+            Code::UnusedVariable => "W0300",
+            Code::UnusedFunction => "W0301",
+            Code::UnusedValue => "W0302",
+            Code::ShadowedVariable => "W0303",
+            Code::NonExhaustiveMatch => "W0304",
+            Code::UnreachablePattern => "W0305",
+            Code::TypeMismatch => "E0500",
+            Code::NoMatchingSignature => "E0501",
+            Code::NonBooleanCondition => "E0502",
+            Code::MissingMain => "E0400",
+            Code::MissingValue => "E0401",
+            Code::IndexOutOfBounds => "E0402",
+            Code::EmptyList => "E0403",
+            Code::IntegerOverflow => "E0404",
+            Code::InvalidConversion => "E0405",
+            Code::DivisionByZero => "E0406",
+            Code::NoMatchingArm => "E0407",
+            Code::EndOfInput => "E0408",
+            Code::ImportCycle => "E0600",
+            Code::UnknownModule => "E0601",
+            Code::ConflictingModules => "E0602",
+        }
+    }
+}
+
+impl std::fmt::Display for Code {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A secondary span attached to a diagnostic, explaining how another part of
+/// the source relates to the issue.
+#[derive(Debug, Clone)]
+pub struct Label {
+    pub location: Location,
+    pub message: String,
+}
+
+/// The single shape used to report problems from every stage of the
+/// toolchain: reading, lexing, parsing, validation and interpretation.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: Code,
+    pub message: String,
+    /// Where the issue is, if it can be tied to the source at all. Boxed,
+    /// being the largest field of the error type of every stage.
+    pub location: Option<Box<Location>>,
+    pub labels: Vec<Label>,
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, code: Code, message: String) -> Self {
+        Self {
+            severity,
+            code,
+            message,
+            location: None,
+            labels: Vec::new(),
+            help: None,
+        }
+    }
+
+    pub fn error(code: Code, message: String) -> Self {
+        Self::new(Severity::Error, code, message)
+    }
+
+    pub fn warning(code: Code, message: String) -> Self {
+        Self::new(Severity::Warning, code, message)
+    }
+
+    pub fn note(code: Code, message: String) -> Self {
+        Self::new(Severity::Note, code, message)
+    }
+
+    pub fn at(mut self, location: Location) -> Self {
+        self.location = Some(Box::new(location));
+        self
+    }
+
+    pub fn with_label(mut self, location: Location, message: String) -> Self {
+        self.labels.push(Label { location, message });
+        self
+    }
+
+    pub fn with_help(mut self, help: String) -> Self {
+        self.help = Some(help);
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if let Some(location) = &self.location {
+            write!(f, "{} ", location)?;
+        }
+
+        write!(f, "{}[{}] {}", self.severity, self.code, self.message)?;
+
+        for label in &self.labels {
+            write!(f, "\n\t{} {}", label.location, label.message)?;
+        }
+
+        if let Some(help) = &self.help {
+            write!(f, "\n\thelp: {}", help)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for Diagnostic {}
+
+pub fn has_errors(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics.iter().any(Diagnostic::is_error)
+}
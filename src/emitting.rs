@@ -0,0 +1,222 @@
+use std::io::Write;
+use std::str::FromStr;
+
+use super::diagnostics::{Diagnostic, Severity};
+use super::lexing::{Location, Position};
+use super::reading::Sources;
+use super::rendering::Renderer;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageFormat {
+    Human,
+    Json,
+    Sarif,
+}
+
+impl FromStr for MessageFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(MessageFormat::Human),
+            "json" => Ok(MessageFormat::Json),
+            "sarif" => Ok(MessageFormat::Sarif),
+            _ => Err(format!("unknown message format \"{}\"", s)),
+        }
+    }
+}
+
+/// Writes diagnostics to a stream in the requested format.
+///
+/// Human and JSON diagnostics are written as soon as they are emitted, one
+/// per record. SARIF wraps every result in a single log, which is only
+/// written by `finish`.
+pub struct Emitter {
+    format: MessageFormat,
+    output: Box<dyn Write>,
+    colored: bool,
+    results: Vec<String>,
+    errors: usize,
+}
+
+impl Emitter {
+    pub fn new(format: MessageFormat, output: Box<dyn Write>, colored: bool) -> Self {
+        Self {
+            format,
+            output,
+            colored,
+            results: Vec::new(),
+            errors: 0,
+        }
+    }
+
+    pub fn emit(&mut self, sources: &Sources, diagnostics: &[Diagnostic]) {
+        for diagnostic in diagnostics {
+            if diagnostic.is_error() {
+                self.errors += 1;
+            }
+
+            let _ = match self.format {
+                MessageFormat::Human => writeln!(
+                    self.output,
+                    "{}\n",
+                    Renderer::new(sources, self.colored).render(diagnostic)
+                ),
+                MessageFormat::Json => writeln!(self.output, "{}", to_json(sources, diagnostic)),
+                MessageFormat::Sarif => {
+                    self.results.push(to_sarif_result(sources, diagnostic));
+                    Ok(())
+                }
+            };
+        }
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.errors > 0
+    }
+
+    pub fn finish(&mut self) {
+        if self.format == MessageFormat::Sarif {
+            let _ = writeln!(
+                self.output,
+                "{{\"$schema\":{},\"version\":\"2.1.0\",\"runs\":[{{\"tool\":{{\"driver\":{{\"name\":\"idem\",\"version\":{}}}}},\"columnKind\":\"utf16CodeUnits\",\"results\":[{}]}}]}}",
+                escape(SARIF_SCHEMA),
+                escape(env!("CARGO_PKG_VERSION")),
+                self.results.join(",")
+            );
+        }
+
+        let _ = self.output.flush();
+    }
+}
+
+/// Serializes a diagnostic as a single line JSON object.
+///
+/// Lines and columns are 1-based, columns counting characters and offsets
+/// bytes, and the end of a span points right after its last character.
+pub fn to_json(sources: &Sources, diagnostic: &Diagnostic) -> String {
+    format!(
+        "{{\"severity\":{},\"code\":{},\"message\":{},{},\"labels\":[{}],\"help\":{}}}",
+        escape(&diagnostic.severity.to_string()),
+        escape(diagnostic.code.as_str()),
+        escape(&diagnostic.message),
+        match &diagnostic.location {
+            Some(location) => json_location(sources, location),
+            None =>
+                "\"path\":null,\"line\":null,\"column\":null,\"end_line\":null,\"end_column\":null,\"offset\":null,\"end_offset\":null"
+                    .to_owned(),
+        },
+        diagnostic
+            .labels
+            .iter()
+            .map(|label| format!(
+                "{{\"message\":{},{}}}",
+                escape(&label.message),
+                json_location(sources, &label.location)
+            ))
+            .collect::<Vec<String>>()
+            .join(","),
+        match &diagnostic.help {
+            Some(help) => escape(help),
+            None => "null".to_owned(),
+        }
+    )
+}
+
+fn json_location(sources: &Sources, location: &Location) -> String {
+    format!(
+        "\"path\":{},\"line\":{},\"column\":{},\"end_line\":{},\"end_column\":{},\"offset\":{},\"end_offset\":{}",
+        escape(&location.path),
+        location.start.line,
+        column(sources, &location.path, &location.start, |_| 1),
+        location.end.line,
+        column(sources, &location.path, &location.end, |_| 1),
+        location.start.offset,
+        location.end.offset
+    )
+}
+
+fn to_sarif_result(sources: &Sources, diagnostic: &Diagnostic) -> String {
+    format!(
+        "{{\"ruleId\":{},\"level\":{},\"message\":{{\"text\":{}}},\"locations\":[{}],\"relatedLocations\":[{}]}}",
+        escape(diagnostic.code.as_str()),
+        escape(match diagnostic.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+        }),
+        escape(&match &diagnostic.help {
+            Some(help) => format!("{}\nhelp: {}", diagnostic.message, help),
+            None => diagnostic.message.clone(),
+        }),
+        match &diagnostic.location {
+            Some(location) => sarif_location(sources, location, None),
+            None => String::new(),
+        },
+        diagnostic
+            .labels
+            .iter()
+            .map(|label| sarif_location(sources, &label.location, Some(&label.message)))
+            .collect::<Vec<String>>()
+            .join(",")
+    )
+}
+
+fn sarif_location(sources: &Sources, location: &Location, message: Option<&str>) -> String {
+    format!(
+        "{{\"physicalLocation\":{{\"artifactLocation\":{{\"uri\":{}}},\"region\":{{\"startLine\":{},\"startColumn\":{},\"endLine\":{},\"endColumn\":{},\"byteOffset\":{},\"byteLength\":{}}}}}{}}}",
+        escape(&location.path),
+        location.start.line,
+        column(sources, &location.path, &location.start, char::len_utf16),
+        location.end.line,
+        column(sources, &location.path, &location.end, char::len_utf16),
+        location.start.offset,
+        location.end.offset - location.start.offset,
+        match message {
+            Some(message) => format!(",\"message\":{{\"text\":{}}}", escape(message)),
+            None => String::new(),
+        }
+    )
+}
+
+/// The 1-based column of a position, counting the width of each character
+/// before it on its line, where the lexer counts a tabulation as four
+/// columns. The lexer's column is kept when the source is not known.
+fn column(sources: &Sources, path: &str, position: &Position, width: fn(char) -> usize) -> usize {
+    let Some(before) = sources
+        .get(path)
+        .and_then(|source| source.get(..position.offset))
+    else {
+        return position.column;
+    };
+
+    let line = match before.rfind('\n') {
+        Some(end) => &before[end + 1..],
+        None => before,
+    };
+
+    1 + line.chars().map(width).sum::<usize>()
+}
+
+/// Quotes a string as a JSON string literal.
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
@@ -1,6 +1,7 @@
 use super::ast::{
-    Branch, Expression, FunctionCall, FunctionDefinition, FunctionPrototype, Literal, Statement,
-    Variable, VariableDefinition, AST,
+    Arm, Block, Branch, Expression, FieldAccess, FunctionCall, FunctionDefinition,
+    FunctionPrototype, Lambda, Let, List, Literal, Match, Pattern, Record, RecordDefinition,
+    Statement, TypeAnnotation, Variable, VariableDefinition, AST,
 };
 
 pub trait Formattable {
@@ -9,11 +10,24 @@ pub trait Formattable {
 
 impl Formattable for AST {
     fn format(&self) -> String {
-        self.statements
-            .iter()
-            .map(|statement| statement.format())
-            .collect::<Vec<String>>()
-            .join("\n\n")
+        let mut string = String::new();
+
+        for (index, statement) in self.statements.iter().enumerate() {
+            if index > 0 {
+                // Imports are grouped together, and so are constants.
+                match (&self.statements[index - 1], statement) {
+                    (Statement::Import(_), Statement::Import(_))
+                    | (Statement::ConstantDefinition(_), Statement::ConstantDefinition(_)) => {
+                        string.push('\n')
+                    }
+                    _ => string.push_str("\n\n"),
+                }
+            }
+
+            string.push_str(&statement.format());
+        }
+
+        string
     }
 }
 
@@ -21,6 +35,13 @@ impl Formattable for Statement {
     fn format(&self) -> String {
         match self {
             Statement::FunctionDefinition(func) => func.format(),
+            Statement::RecordDefinition(record) => record.format(),
+            Statement::Import(import) => format!("import {}", escape(&import.path)),
+            Statement::ConstantDefinition(constant) => format!(
+                "const {} = {}",
+                constant.variable.format(),
+                constant.value.format()
+            ),
         }
     }
 }
@@ -40,9 +61,23 @@ impl Formattable for FunctionDefinition {
     }
 }
 
-impl Formattable for FunctionPrototype {
+impl Formattable for RecordDefinition {
     fn format(&self) -> String {
         format!(
+            "record {} {{\n{}\n}}",
+            self.name,
+            self.fields
+                .iter()
+                .map(|field| format!("\t{}: {}", field.name, field.r#type.format()))
+                .collect::<Vec<String>>()
+                .join("\n")
+        )
+    }
+}
+
+impl Formattable for FunctionPrototype {
+    fn format(&self) -> String {
+        let mut string = format!(
             "{}({})",
             self.name,
             self.arguments
@@ -50,32 +85,173 @@ impl Formattable for FunctionPrototype {
                 .map(|arg| arg.format())
                 .collect::<Vec<String>>()
                 .join(", ")
-        )
+        );
+
+        if let Some(return_type) = &self.return_type {
+            string.push_str(&format!(": {}", return_type.format()));
+        }
+
+        string
     }
 }
 
 impl Formattable for VariableDefinition {
     fn format(&self) -> String {
-        self.name.clone()
+        match &self.r#type {
+            Some(r#type) => format!("{}: {}", self.name, r#type.format()),
+            None => self.name.clone(),
+        }
+    }
+}
+
+impl Formattable for TypeAnnotation {
+    fn format(&self) -> String {
+        match self {
+            TypeAnnotation::Named(named) => named.name.clone(),
+            TypeAnnotation::List(list) => format!("[{}]", list.element.format()),
+        }
     }
 }
 
 impl Formattable for Expression {
     fn format(&self) -> String {
         match self {
+            Expression::Block(block) => block.format(),
             Expression::Literal(literal) => literal.format(),
             Expression::Variable(variable) => variable.format(),
             Expression::Branch(branch) => branch.format(),
+            Expression::Match(r#match) => r#match.format(),
+            Expression::Let(binding) => binding.format(),
+            Expression::List(list) => list.format(),
+            Expression::Record(record) => record.format(),
+            Expression::FieldAccess(access) => access.format(),
+            Expression::Lambda(lambda) => lambda.format(),
             Expression::FunctionCall(call) => call.format(),
             Expression::Empty => String::new(),
         }
     }
 }
 
+impl Formattable for Block {
+    fn format(&self) -> String {
+        self.expressions
+            .iter()
+            .map(|expression| expression.format())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
 impl Formattable for Literal {
     fn format(&self) -> String {
         match self {
+            Literal::Bool(boolean) => format!("{}", boolean.value),
             Literal::Number(num) => format!("{}", num.value),
+            Literal::Float(float) => float.raw.clone(),
+            Literal::String(string) => escape(&string.value),
+        }
+    }
+}
+
+/// Quotes a string the way it is written in sources.
+fn escape(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len() + 2);
+    escaped.push('"');
+
+    for c in string.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            '\0' => escaped.push_str("\\0"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+impl Formattable for Let {
+    fn format(&self) -> String {
+        format!("let {} = {}", self.variable.format(), self.value.format())
+    }
+}
+
+impl Formattable for List {
+    fn format(&self) -> String {
+        format!(
+            "[{}]",
+            self.elements
+                .iter()
+                .map(|element| element.format())
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
+
+impl Formattable for Record {
+    fn format(&self) -> String {
+        if self.fields.is_empty() {
+            return "{}".to_owned();
+        }
+
+        format!(
+            "{{ {} }}",
+            self.fields
+                .iter()
+                .map(|field| format!("{}: {}", field.name, field.value.format()))
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
+
+impl Formattable for FieldAccess {
+    fn format(&self) -> String {
+        format!(
+            "{}.{}",
+            parenthesize(&self.record, precedence(&self.record) < u8::MAX),
+            self.field
+        )
+    }
+}
+
+impl Formattable for Lambda {
+    fn format(&self) -> String {
+        let arguments = if self.arguments.is_empty() {
+            "||".to_owned()
+        } else {
+            format!(
+                "|{}|",
+                self.arguments
+                    .iter()
+                    .map(|arg| arg.format())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )
+        };
+
+        match &*self.body {
+            Expression::Block(_) => format!(
+                "{} {{\n{}\n}}",
+                arguments,
+                self.body
+                    .format()
+                    .lines()
+                    .map(|line| format!("\t{}", line))
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            ),
+            // A record would be read as the block of the lambda.
+            _ => format!(
+                "{} {}",
+                arguments,
+                parenthesize(&self.body, matches!(*self.body, Expression::Record(_)))
+            ),
         }
     }
 }
@@ -88,6 +264,27 @@ impl Formattable for Variable {
 
 impl Formattable for FunctionCall {
     fn format(&self) -> String {
+        match (self.operator, self.parameters.as_slice()) {
+            (Some(operator), [operand]) => {
+                return format!(
+                    "{}{}",
+                    operator.symbol(),
+                    parenthesize(operand, precedence(operand) < operator.precedence())
+                );
+            }
+            (Some(operator), [left, right]) => {
+                // Operators being left associative, a right operand of the
+                // same precedence needs parentheses to keep its grouping.
+                return format!(
+                    "{} {} {}",
+                    parenthesize(left, precedence(left) < operator.precedence()),
+                    operator.symbol(),
+                    parenthesize(right, precedence(right) <= operator.precedence())
+                );
+            }
+            _ => {}
+        }
+
         format!(
             "{}({})",
             self.name,
@@ -100,6 +297,27 @@ impl Formattable for FunctionCall {
     }
 }
 
+/// How tightly an expression holds together when it is an operand.
+fn precedence(expression: &Expression) -> u8 {
+    match expression {
+        Expression::FunctionCall(FunctionCall {
+            operator: Some(operator),
+            ..
+        }) => operator.precedence(),
+        // A lambda body extends as far as possible.
+        Expression::Lambda(_) => 0,
+        _ => u8::MAX,
+    }
+}
+
+fn parenthesize(expression: &Expression, needed: bool) -> String {
+    if needed {
+        format!("({})", expression.format())
+    } else {
+        expression.format()
+    }
+}
+
 impl Formattable for Branch {
     fn format(&self) -> String {
         let mut string = format!(
@@ -113,8 +331,12 @@ impl Formattable for Branch {
                 .join("\n"),
         );
 
-        match *self.r#else {
+        match &*self.r#else {
             Expression::Empty => {}
+            // Chains are kept flat rather than nested in blocks.
+            Expression::Branch(branch) => {
+                string.push_str(&format!(" else {}", branch.format()));
+            }
             _ => {
                 string.push_str(&format!(
                     " else {{\n{}\n}}",
@@ -131,3 +353,49 @@ impl Formattable for Branch {
         string
     }
 }
+
+impl Formattable for Match {
+    fn format(&self) -> String {
+        format!(
+            "match {} {{\n{}\n}}",
+            self.value.format(),
+            self.arms
+                .iter()
+                .map(|arm| arm.format())
+                .collect::<Vec<String>>()
+                .join("\n")
+                .lines()
+                .map(|line| format!("\t{}", line))
+                .collect::<Vec<String>>()
+                .join("\n")
+        )
+    }
+}
+
+impl Formattable for Arm {
+    fn format(&self) -> String {
+        let pattern = match &self.pattern {
+            Pattern::Literal(literal) => literal.format(),
+            Pattern::Wildcard(_) => "_".to_owned(),
+        };
+
+        match &self.body {
+            Expression::Block(_) => format!(
+                "{} => {{\n{}\n}}",
+                pattern,
+                self.body
+                    .format()
+                    .lines()
+                    .map(|line| format!("\t{}", line))
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            ),
+            // A record would be read as the block of the arm.
+            _ => format!(
+                "{} => {}",
+                pattern,
+                parenthesize(&self.body, matches!(self.body, Expression::Record(_)))
+            ),
+        }
+    }
+}
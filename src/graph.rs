@@ -6,7 +6,7 @@ pub struct Node {
 
 impl std::fmt::Display for Node {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{} [label=\"{}\"];", self.id, self.label)
+        write!(f, "\"{}\" [label=\"{}\"];", self.id, self.label)
     }
 }
 
@@ -18,7 +18,7 @@ pub struct Edge {
 
 impl std::fmt::Display for Edge {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{} -> {};", self.source, self.destination)
+        write!(f, "\"{}\" -> \"{}\";", self.source, self.destination)
     }
 }
 
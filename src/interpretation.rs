@@ -1,162 +1,672 @@
-use std::collections::HashMap;
-use std::error::Error;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, StdinLock, Stdout, Write};
 
 use opentelemetry::trace::Tracer;
 use opentelemetry::{global, Context};
 
-use super::ast::{Expression, FunctionDefinition, Literal, Statement, AST};
-use super::native::{add, and, equal, multiply, not, or, print, sub, xor};
+use super::ast::{
+    Callee, ConstantDefinition, Expression, FunctionDefinition, Literal, Operator, Pattern,
+    Statement, TypeAnnotation, VariableDefinition, AST,
+};
+use super::diagnostics::{Code, Diagnostic};
+use super::formatting::Formattable;
+use super::native::{
+    add, add_floats, and, concat, divide, divide_floats, end_of_input, equal, filter, fold, get,
+    greater, greater_equal, head, length, less, less_equal, map, modulo, modulo_floats, multiply,
+    multiply_floats, native_functions_map, negate, negate_float, not, not_equal, or, output_error,
+    print, push, read_line, read_number, string_length, sub, sub_floats, tail, to_float, to_int,
+    xor,
+};
+use super::typing::Signature;
+use super::value::{Function, Value};
 
-pub struct Interpreter {}
+/// Runs programs, which read their input from and write their output to the
+/// given handles.
+pub struct Interpreter<R = StdinLock<'static>, W = Stdout> {
+    input: R,
+    output: W,
+}
 
-impl Interpreter {
-    pub fn run(&mut self, ast: &AST) -> Result<(), Box<dyn Error>> {
-        let mut functions = HashMap::new();
+impl Default for Interpreter {
+    /// An interpreter reading the standard input and writing to the standard
+    /// output.
+    fn default() -> Self {
+        Interpreter::new(std::io::stdin().lock(), std::io::stdout())
+    }
+}
+
+/// What a program defines, which every function it runs can refer to.
+struct Program<'a> {
+    functions: HashMap<&'a str, &'a FunctionDefinition>,
+    constants: HashMap<&'a str, &'a ConstantDefinition>,
+    /// The value of every constant evaluated so far.
+    values: HashMap<&'a str, Value<'a>>,
+    /// The signatures of native functions, telling how many parameters each
+    /// one accepts.
+    natives: HashMap<&'a str, Vec<Signature>>,
+}
+
+impl<R: BufRead, W: Write> Interpreter<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Interpreter { input, output }
+    }
+
+    /// What the programs run so far wrote.
+    pub fn output(&self) -> &W {
+        &self.output
+    }
+
+    /// Runs a program, calling `main` with the given command-line arguments,
+    /// and returns its exit status.
+    pub fn run(&mut self, ast: &AST, arguments: &[String]) -> Result<i32, Diagnostic> {
+        let mut program = Program {
+            functions: HashMap::new(),
+            constants: HashMap::new(),
+            values: HashMap::new(),
+            natives: native_functions_map(),
+        };
         for statement in &ast.statements {
-            let Statement::FunctionDefinition(function) = statement;
-            functions.insert(function.prototype.name.as_str(), function);
+            match statement {
+                Statement::FunctionDefinition(function) => {
+                    program
+                        .functions
+                        .insert(function.prototype.name.as_str(), function.as_ref());
+                }
+                Statement::ConstantDefinition(constant) => {
+                    program
+                        .constants
+                        .insert(constant.variable.name.as_str(), constant.as_ref());
+                }
+                _ => {}
+            }
         }
 
-        if let Some(main) = ast.statements.iter().find(|statement| {
-            let Statement::FunctionDefinition(function) = statement;
-            function.prototype.name == "main"
-        }) {
-            let Statement::FunctionDefinition(function) = main;
+        if let Some(function) = program.functions.get("main").copied() {
+            let parameters = &function.prototype.arguments;
+            if parameters.len() != arguments.len() {
+                return Err(Diagnostic::error(
+                    Code::ArgumentsCount,
+                    format!(
+                        "main accepts {} arguments but received {} command-line arguments",
+                        parameters.len(),
+                        arguments.len()
+                    ),
+                )
+                .at(function.prototype.location.clone())
+                .with_help("pass them after `--`, as in `idem run main.id -- 1 2`".to_owned()));
+            }
+
+            let mut variables = HashMap::new();
+            for (parameter, argument) in parameters.iter().zip(arguments) {
+                variables.insert(
+                    parameter.name.as_str(),
+                    argument_value(argument, parameter)?,
+                );
+            }
+
+            // Every constant is evaluated once, before `main` runs.
+            for statement in &ast.statements {
+                if let Statement::ConstantDefinition(constant) = statement {
+                    self.constant(&constant.variable.name, &mut program, &Context::current())?;
+                }
+            }
 
             let tracer = global::tracer("");
             let span = tracer.start("main");
 
-            tracer.with_span(span, |ctx| {
-                self.interpretate(&function.body, &mut HashMap::new(), &functions, &ctx)
-                    .unwrap()
+            let value = tracer.with_span(span, |ctx| {
+                self.interpretate(&function.body, &mut variables, &mut program, &ctx)
             });
 
-            Ok(())
+            // What a failing program wrote is kept, as it helps understand
+            // the failure.
+            self.output.flush().map_err(output_error)?;
+            let value = value?;
+
+            exit_status(value)
+                .map_err(|diagnostic| diagnostic.at(function.prototype.location.clone()))
         } else {
-            panic!("no main function")
+            Err(Diagnostic::error(
+                Code::MissingMain,
+                "no main function".to_owned(),
+            ))
         }
     }
 
-    fn interpretate(
+    fn interpretate<'a>(
         &mut self,
-        expression: &Expression,
-        variables: &mut HashMap<&str, u32>,
-        functions: &HashMap<&str, &FunctionDefinition>,
+        expression: &'a Expression,
+        variables: &mut HashMap<&'a str, Value<'a>>,
+        program: &mut Program<'a>,
         ctx: &Context,
-    ) -> Result<Option<u32>, Box<dyn Error>> {
+    ) -> Result<Option<Value<'a>>, Diagnostic> {
         match expression {
             Expression::Empty => Ok(None),
-            Expression::Literal(literal) => match literal {
-                Literal::Number(num) => Ok(Some(num.value)),
+            Expression::Block(block) => {
+                // Bindings made in a block do not outlive it.
+                let mut scope = variables.clone();
+
+                let mut value = None;
+                for expression in &block.expressions {
+                    value = self.interpretate(expression, &mut scope, program, ctx)?;
+                }
+
+                Ok(value)
+            }
+            Expression::Let(binding) => {
+                let value = self.evaluate(&binding.value, variables, program, ctx)?;
+                variables.insert(binding.variable.name.as_str(), value);
+
+                Ok(None)
+            }
+            Expression::Literal(literal) => Ok(Some(literal_value(literal))),
+            Expression::List(list) => {
+                let mut elements = Vec::with_capacity(list.elements.len());
+                for element in &list.elements {
+                    elements.push(self.evaluate(element, variables, program, ctx)?);
+                }
+
+                Ok(Some(Value::List(elements)))
+            }
+            Expression::Record(record) => {
+                let mut fields = BTreeMap::new();
+                for field in &record.fields {
+                    let value = self.evaluate(&field.value, variables, program, ctx)?;
+                    fields.insert(field.name.clone(), value);
+                }
+
+                Ok(Some(Value::Record(fields)))
+            }
+            Expression::FieldAccess(access) => {
+                let record = self.evaluate(&access.record, variables, program, ctx)?;
+
+                record
+                    .field(&access.field)
+                    .cloned()
+                    .map(Some)
+                    .map_err(|diagnostic| diagnostic.at(access.location.clone()))
+            }
+            Expression::Lambda(lambda) => Ok(Some(Value::Function(Function::Lambda(
+                lambda,
+                variables.clone(),
+            )))),
+            Expression::Variable(var) => match Callee::lookup(
+                || stored(variables, program, &var.name),
+                || program.functions.get(var.name.as_str()).copied(),
+            ) {
+                Callee::Value(Some(value)) => Ok(Some(value)),
+                Callee::Value(None) => self.constant(&var.name, program, ctx).map(Some),
+                Callee::Function(function) => Ok(Some(Value::Function(Function::Named(function)))),
+                Callee::Native => Err(Diagnostic::error(
+                    Code::UnknownVariable,
+                    format!("unknown variable \"{}\"", var.name),
+                )
+                .at(var.location.clone())),
             },
-            Expression::Variable(var) => Ok(Some(*variables.get(var.name.as_str()).unwrap())),
             Expression::Branch(branch) => {
-                if self
-                    .interpretate(
-                        &*branch.condition,
-                        variables,
-                        functions,
-                        &Context::current(),
-                    )?
-                    .unwrap()
-                    == 1
-                {
-                    self.interpretate(&*branch.then, variables, functions, ctx)
+                let condition =
+                    self.evaluate(&branch.condition, variables, program, &Context::current())?;
+                let condition = condition.boolean().map_err(|diagnostic| {
+                    match branch.condition.location() {
+                        Some(location) => diagnostic.at(location.clone()),
+                        None => diagnostic,
+                    }
+                })?;
+
+                if condition {
+                    self.interpretate(&branch.then, variables, program, ctx)
                 } else {
-                    self.interpretate(&*branch.r#else, variables, functions, ctx)
+                    self.interpretate(&branch.r#else, variables, program, ctx)
                 }
             }
+            Expression::Match(r#match) => {
+                let value = self.evaluate(&r#match.value, variables, program, ctx)?;
+
+                for arm in &r#match.arms {
+                    let matches = match &arm.pattern {
+                        Pattern::Literal(literal) => literal_value(literal) == value,
+                        Pattern::Wildcard(_) => true,
+                    };
+
+                    if matches {
+                        return self.interpretate(&arm.body, variables, program, ctx);
+                    }
+                }
+
+                Err(Diagnostic::error(
+                    Code::NoMatchingArm,
+                    format!("no arm matches the value `{}`", value),
+                )
+                .at(r#match.location.clone()))
+            }
             Expression::FunctionCall(call) => {
                 let tracer = global::tracer("");
                 let span = tracer.start_with_context(call.name.to_owned(), ctx);
 
-                tracer.with_span(span, |ctx| match call.name.as_str() {
-                    "or" => Ok(Some(or(
-                        self.interpretate(&call.parameters[0], variables, functions, &ctx)
-                            .unwrap()
-                            .unwrap(),
-                        self.interpretate(&call.parameters[1], variables, functions, &ctx)
-                            .unwrap()
-                            .unwrap(),
-                    ))),
-                    "and" => Ok(Some(and(
-                        self.interpretate(&call.parameters[0], variables, functions, &ctx)
-                            .unwrap()
-                            .unwrap(),
-                        self.interpretate(&call.parameters[1], variables, functions, &ctx)
-                            .unwrap()
-                            .unwrap(),
-                    ))),
-                    "xor" => Ok(Some(xor(
-                        self.interpretate(&call.parameters[0], variables, functions, &ctx)
-                            .unwrap()
-                            .unwrap(),
-                        self.interpretate(&call.parameters[0], variables, functions, &ctx)
-                            .unwrap()
-                            .unwrap(),
-                    ))),
-
-                    "not" => Ok(Some(not(self
-                        .interpretate(&call.parameters[0], variables, functions, &ctx)
-                        .unwrap()
-                        .unwrap()))),
-                    "equal" => Ok(Some(equal(
-                        self.interpretate(&call.parameters[0], variables, functions, &ctx)
-                            .unwrap()
-                            .unwrap(),
-                        self.interpretate(&call.parameters[1], variables, functions, &ctx)
-                            .unwrap()
-                            .unwrap(),
-                    ))),
-                    "add" => Ok(Some(add(
-                        self.interpretate(&call.parameters[0], variables, functions, &ctx)
-                            .unwrap()
-                            .unwrap(),
-                        self.interpretate(&call.parameters[1], variables, functions, &ctx)
-                            .unwrap()
-                            .unwrap(),
-                    ))),
-                    "sub" => Ok(Some(sub(
-                        self.interpretate(&call.parameters[0], variables, functions, &ctx)
-                            .unwrap()
-                            .unwrap(),
-                        self.interpretate(&call.parameters[1], variables, functions, &ctx)
-                            .unwrap()
-                            .unwrap(),
-                    ))),
-                    "multiply" => Ok(Some(multiply(
-                        self.interpretate(&call.parameters[0], variables, functions, &ctx)
-                            .unwrap()
-                            .unwrap(),
-                        self.interpretate(&call.parameters[1], variables, functions, &ctx)
-                            .unwrap()
-                            .unwrap(),
-                    ))),
-                    "print" => {
-                        print(
-                            self.interpretate(&call.parameters[0], variables, functions, &ctx)
-                                .unwrap()
-                                .unwrap(),
+                tracer.with_span(span, |ctx| {
+                    if let (Some(operator @ (Operator::And | Operator::Or)), [left, right]) =
+                        (call.operator, call.parameters.as_slice())
+                    {
+                        return self.short_circuit(
+                            operator == Operator::Or,
+                            left,
+                            right,
+                            variables,
+                            program,
+                            &ctx,
                         );
-                        Ok(None)
                     }
-                    _ => {
-                        let function = functions.get(call.name.as_str()).unwrap();
-                        let mut local_variables = HashMap::new();
-
-                        for i in 0..call.parameters.len() {
-                            local_variables.insert(
-                                function.prototype.arguments[i].name.as_str(),
-                                self.interpretate(&call.parameters[i], variables, functions, &ctx)
-                                    .unwrap()
-                                    .unwrap(),
-                            );
-                        }
 
-                        self.interpretate(&function.body, &mut local_variables, functions, &ctx)
+                    let mut parameters = Vec::with_capacity(call.parameters.len());
+                    for parameter in &call.parameters {
+                        parameters.push(self.evaluate(parameter, variables, program, &ctx)?);
                     }
+
+                    let callee = call.callee(
+                        || stored(variables, program, &call.name),
+                        || program.functions.get(call.name.as_str()).copied(),
+                    );
+
+                    let function = match callee {
+                        Callee::Value(value) => {
+                            let value = match value {
+                                Some(value) => value,
+                                None => self.constant(&call.name, program, &ctx)?,
+                            };
+
+                            Some(
+                                value
+                                    .function()
+                                    .map_err(|diagnostic| diagnostic.at(call.location.clone()))?
+                                    .clone(),
+                            )
+                        }
+                        Callee::Function(function) => Some(Function::Named(function)),
+                        Callee::Native => None,
+                    };
+
+                    let result = match function {
+                        Some(function) => self.apply(&function, parameters, program, &ctx),
+                        None => self.call_native(&call.name, &parameters, program, &ctx),
+                    };
+
+                    // Errors raised within other functions already point at
+                    // where they happened.
+                    result.map_err(|diagnostic| match diagnostic.location {
+                        Some(_) => diagnostic,
+                        None => diagnostic.at(call.location.clone()),
+                    })
                 })
             }
         }
     }
+
+    /// Evaluates `&&` or `||`, whose right operand is only evaluated when the
+    /// left one does not decide the result, so that it can be guarded by it.
+    fn short_circuit<'a>(
+        &mut self,
+        decisive: bool,
+        left: &'a Expression,
+        right: &'a Expression,
+        variables: &mut HashMap<&'a str, Value<'a>>,
+        program: &mut Program<'a>,
+        ctx: &Context,
+    ) -> Result<Option<Value<'a>>, Diagnostic> {
+        for operand in [left, right] {
+            let value = self
+                .evaluate(operand, variables, program, ctx)?
+                .boolean()
+                .map_err(|diagnostic| match operand.location() {
+                    Some(location) => diagnostic.at(location.clone()),
+                    None => diagnostic,
+                })?;
+
+            if value == decisive {
+                return Ok(Some(Value::Bool(value)));
+            }
+        }
+
+        Ok(Some(Value::Bool(!decisive)))
+    }
+
+    /// The value of a constant, evaluated the first time it is needed.
+    fn constant<'a>(
+        &mut self,
+        name: &str,
+        program: &mut Program<'a>,
+        ctx: &Context,
+    ) -> Result<Value<'a>, Diagnostic> {
+        if let Some(value) = program.values.get(name) {
+            return Ok(value.clone());
+        }
+
+        let constant = program.constants[name];
+        let value = self.evaluate(&constant.value, &mut HashMap::new(), program, ctx)?;
+        program
+            .values
+            .insert(constant.variable.name.as_str(), value.clone());
+
+        Ok(value)
+    }
+
+    /// Calls a function value, checking it accepts as many parameters as
+    /// given.
+    fn apply<'a>(
+        &mut self,
+        function: &Function<'a>,
+        parameters: Vec<Value<'a>>,
+        program: &mut Program<'a>,
+        ctx: &Context,
+    ) -> Result<Option<Value<'a>>, Diagnostic> {
+        if function.arity() != parameters.len() {
+            return Err(Diagnostic::error(
+                Code::ArgumentsCount,
+                format!(
+                    "{} accepts {} arguments but received {} parameters",
+                    Value::Function(function.clone()),
+                    function.arity(),
+                    parameters.len()
+                ),
+            ));
+        }
+
+        match function {
+            Function::Named(function) => {
+                let mut local_variables = function
+                    .prototype
+                    .arguments
+                    .iter()
+                    .map(|argument| argument.name.as_str())
+                    .zip(parameters)
+                    .collect();
+
+                self.interpretate(&function.body, &mut local_variables, program, ctx)
+            }
+            Function::Lambda(lambda, captured) => {
+                let mut local_variables = captured.clone();
+                local_variables.extend(
+                    lambda
+                        .arguments
+                        .iter()
+                        .map(|argument| argument.name.as_str())
+                        .zip(parameters),
+                );
+
+                self.interpretate(&lambda.body, &mut local_variables, program, ctx)
+            }
+        }
+    }
+
+    /// Same as `apply`, for functions which are expected to produce a value.
+    fn apply_for_value<'a>(
+        &mut self,
+        function: &Function<'a>,
+        parameters: Vec<Value<'a>>,
+        program: &mut Program<'a>,
+        ctx: &Context,
+    ) -> Result<Value<'a>, Diagnostic> {
+        self.apply(function, parameters, program, ctx)?
+            .ok_or_else(|| {
+                Diagnostic::error(
+                    Code::MissingValue,
+                    format!("{} has no value", Value::Function(function.clone())),
+                )
+            })
+    }
+
+    fn call_native<'a>(
+        &mut self,
+        name: &str,
+        parameters: &[Value<'a>],
+        program: &mut Program<'a>,
+        ctx: &Context,
+    ) -> Result<Option<Value<'a>>, Diagnostic> {
+        // Overloads of a native function all share the same arity.
+        if let Some(overloads) = program.natives.get(name) {
+            let arity = overloads[0].parameters.len();
+            if arity != parameters.len() {
+                return Err(Diagnostic::error(
+                    Code::ArgumentsCount,
+                    format!(
+                        "function \"{}\" accepts {} arguments but received {} parameters",
+                        name,
+                        arity,
+                        parameters.len()
+                    ),
+                ));
+            }
+        }
+
+        match name {
+            "or" => Ok(Some(Value::Bool(or(
+                parameters[0].boolean()?,
+                parameters[1].boolean()?,
+            )))),
+            "and" => Ok(Some(Value::Bool(and(
+                parameters[0].boolean()?,
+                parameters[1].boolean()?,
+            )))),
+            "xor" => Ok(Some(Value::Bool(xor(
+                parameters[0].boolean()?,
+                parameters[1].boolean()?,
+            )))),
+            "not" => Ok(Some(Value::Bool(not(parameters[0].boolean()?)))),
+            "equal" => Ok(Some(Value::Bool(equal(&parameters[0], &parameters[1])))),
+            "not_equal" => Ok(Some(Value::Bool(not_equal(&parameters[0], &parameters[1])))),
+            "negate" => match &parameters[0] {
+                Value::Float(a) => Ok(Some(Value::Float(negate_float(*a)))),
+                a => Ok(Some(Value::Number(negate(a.number()?)?))),
+            },
+            "add" => match (&parameters[0], &parameters[1]) {
+                (Value::Float(a), Value::Float(b)) => Ok(Some(Value::Float(add_floats(*a, *b)))),
+                (a, b) => Ok(Some(Value::Number(add(a.number()?, b.number()?)?))),
+            },
+            "sub" => match (&parameters[0], &parameters[1]) {
+                (Value::Float(a), Value::Float(b)) => Ok(Some(Value::Float(sub_floats(*a, *b)))),
+                (a, b) => Ok(Some(Value::Number(sub(a.number()?, b.number()?)?))),
+            },
+            "multiply" => match (&parameters[0], &parameters[1]) {
+                (Value::Float(a), Value::Float(b)) => {
+                    Ok(Some(Value::Float(multiply_floats(*a, *b))))
+                }
+                (a, b) => Ok(Some(Value::Number(multiply(a.number()?, b.number()?)?))),
+            },
+            "divide" => match (&parameters[0], &parameters[1]) {
+                (Value::Float(a), Value::Float(b)) => {
+                    Ok(Some(Value::Float(divide_floats(*a, *b)?)))
+                }
+                (a, b) => Ok(Some(Value::Number(divide(a.number()?, b.number()?)?))),
+            },
+            "modulo" => match (&parameters[0], &parameters[1]) {
+                (Value::Float(a), Value::Float(b)) => {
+                    Ok(Some(Value::Float(modulo_floats(*a, *b)?)))
+                }
+                (a, b) => Ok(Some(Value::Number(modulo(a.number()?, b.number()?)?))),
+            },
+            "less" => match (&parameters[0], &parameters[1]) {
+                (Value::Float(a), Value::Float(b)) => Ok(Some(Value::Bool(less(a, b)))),
+                (a, b) => Ok(Some(Value::Bool(less(a.number()?, b.number()?)))),
+            },
+            "greater" => match (&parameters[0], &parameters[1]) {
+                (Value::Float(a), Value::Float(b)) => Ok(Some(Value::Bool(greater(a, b)))),
+                (a, b) => Ok(Some(Value::Bool(greater(a.number()?, b.number()?)))),
+            },
+            "less_equal" => match (&parameters[0], &parameters[1]) {
+                (Value::Float(a), Value::Float(b)) => Ok(Some(Value::Bool(less_equal(a, b)))),
+                (a, b) => Ok(Some(Value::Bool(less_equal(a.number()?, b.number()?)))),
+            },
+            "greater_equal" => match (&parameters[0], &parameters[1]) {
+                (Value::Float(a), Value::Float(b)) => Ok(Some(Value::Bool(greater_equal(a, b)))),
+                (a, b) => Ok(Some(Value::Bool(greater_equal(a.number()?, b.number()?)))),
+            },
+            "to_float" => Ok(Some(Value::Float(to_float(parameters[0].number()?)))),
+            "to_int" => Ok(Some(Value::Number(to_int(parameters[0].float()?)?))),
+            "length" => match &parameters[0] {
+                Value::String(string) => Ok(Some(Value::Number(string_length(string)))),
+                list => Ok(Some(Value::Number(length(list.list()?)))),
+            },
+            "get" => Ok(Some(get(parameters[0].list()?, parameters[1].number()?)?)),
+            "push" => Ok(Some(Value::List(push(
+                parameters[0].list()?,
+                parameters[1].clone(),
+            )))),
+            "head" => Ok(Some(head(parameters[0].list()?)?)),
+            "tail" => Ok(Some(Value::List(tail(parameters[0].list()?)?))),
+            "map" => {
+                let function = parameters[1].function()?;
+
+                Ok(Some(Value::List(map(parameters[0].list()?, |element| {
+                    self.apply_for_value(function, vec![element], program, ctx)
+                })?)))
+            }
+            "filter" => {
+                let function = parameters[1].function()?;
+
+                Ok(Some(Value::List(filter(
+                    parameters[0].list()?,
+                    |element| {
+                        self.apply_for_value(function, vec![element], program, ctx)?
+                            .boolean()
+                    },
+                )?)))
+            }
+            "fold" => {
+                let function = parameters[2].function()?;
+
+                Ok(Some(fold(
+                    parameters[0].list()?,
+                    parameters[1].clone(),
+                    |accumulator, element| {
+                        self.apply_for_value(function, vec![accumulator, element], program, ctx)
+                    },
+                )?))
+            }
+            "concat" => Ok(Some(Value::String(concat(
+                parameters[0].string()?,
+                parameters[1].string()?,
+            )))),
+            "print" => {
+                print(&mut self.output, &parameters[0])?;
+                Ok(None)
+            }
+            "read_line" => Ok(Some(Value::String(read_line(&mut self.input)?))),
+            "read_number" => Ok(Some(Value::Number(read_number(&mut self.input)?))),
+            "end_of_input" => Ok(Some(Value::Bool(end_of_input(&mut self.input)?))),
+            _ => Err(Diagnostic::error(
+                Code::UnknownFunction,
+                format!("unknwon function \"{}\"", name),
+            )),
+        }
+    }
+
+    /// Interpretates an expression which is expected to produce a value.
+    fn evaluate<'a>(
+        &mut self,
+        expression: &'a Expression,
+        variables: &mut HashMap<&'a str, Value<'a>>,
+        program: &mut Program<'a>,
+        ctx: &Context,
+    ) -> Result<Value<'a>, Diagnostic> {
+        self.interpretate(expression, variables, program, ctx)?
+            .ok_or_else(|| {
+                let diagnostic =
+                    Diagnostic::error(Code::MissingValue, "expression has no value".to_owned());
+
+                match expression {
+                    Expression::FunctionCall(call) => diagnostic.at(call.location.clone()),
+                    Expression::Branch(branch) => diagnostic.at(branch.location.clone()),
+                    _ => diagnostic,
+                }
+            })
+    }
+}
+
+/// Looks up the value of a variable or a constant, the latter being `None`
+/// until it has been evaluated, which is left to the caller.
+fn stored<'a>(
+    variables: &HashMap<&'a str, Value<'a>>,
+    program: &Program<'a>,
+    name: &str,
+) -> Option<Option<Value<'a>>> {
+    match variables.get(name) {
+        Some(value) => Some(Some(value.clone())),
+        None => program.constants.contains_key(name).then_some(None),
+    }
+}
+
+/// Parses a command-line argument into the value of a parameter of `main`,
+/// according to its annotation or, lacking one, to what it looks like.
+fn argument_value<'a>(
+    argument: &str,
+    parameter: &VariableDefinition,
+) -> Result<Value<'a>, Diagnostic> {
+    let invalid = |r#type: &str| {
+        Diagnostic::error(
+            Code::InvalidConversion,
+            format!(
+                "argument \"{}\" of {} cannot be converted to {}",
+                argument, parameter.name, r#type
+            ),
+        )
+        .at(parameter.location.clone())
+    };
+
+    match &parameter.r#type {
+        Some(TypeAnnotation::Named(named)) => match named.name.as_str() {
+            "Int" => argument
+                .parse()
+                .map(Value::Number)
+                .map_err(|_| invalid("an integer")),
+            "Float" => argument
+                .parse()
+                .map(Value::Float)
+                .map_err(|_| invalid("a float")),
+            "Bool" => argument
+                .parse()
+                .map(Value::Bool)
+                .map_err(|_| invalid("a boolean")),
+            "String" => Ok(Value::String(argument.to_owned())),
+            name => Err(invalid(name)),
+        },
+        Some(annotation @ TypeAnnotation::List(_)) => Err(invalid(&annotation.format())),
+        None => Ok(if let Ok(number) = argument.parse() {
+            Value::Number(number)
+        } else if let Some(float) = argument.parse::<f64>().ok().filter(|f| f.is_finite()) {
+            Value::Float(float)
+        } else if let Ok(boolean) = argument.parse() {
+            Value::Bool(boolean)
+        } else {
+            Value::String(argument.to_owned())
+        }),
+    }
+}
+
+/// The exit status of a program, given by the integer `main` returns.
+/// Statuses are limited to what every platform reports unchanged, as Unix
+/// keeps only their lowest 8 bits.
+fn exit_status(value: Option<Value>) -> Result<i32, Diagnostic> {
+    match value {
+        None => Ok(0),
+        Some(value) => {
+            let status = value.number()?;
+
+            match u8::try_from(status) {
+                Ok(status) => Ok(i32::from(status)),
+                Err(_) => Err(Diagnostic::error(
+                    Code::InvalidConversion,
+                    format!("{} cannot be used as an exit status", status),
+                )
+                .with_help("exit statuses range from 0 to 255".to_owned())),
+            }
+        }
+    }
+}
+
+fn literal_value<'a>(literal: &Literal) -> Value<'a> {
+    match literal {
+        Literal::Bool(boolean) => Value::Bool(boolean.value),
+        Literal::Number(num) => Value::Number(num.value),
+        Literal::Float(float) => Value::Float(float.value),
+        Literal::String(string) => Value::String(string.value.clone()),
+    }
 }
@@ -1,25 +1,58 @@
 use std::str::Chars;
 
+use super::diagnostics::{Code, Diagnostic};
+
 #[derive(Debug)]
 pub struct LocatedToken {
 	pub location: Location,
 	pub token: Token,
 }
 
-#[derive(Debug, Clone)]
+/// A span of source code, from its first character to right after its last
+/// one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct Location {
 	pub path: String,
+	pub start: Position,
+	pub end: Position,
+}
+
+/// A position in a source file. Lines and columns start at 1, a tabulation
+/// counting as four columns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Position {
+	pub offset: usize,
 	pub line: usize,
 	pub column: usize,
 }
 
+impl std::fmt::Display for Location {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "{}:{}:{}", self.path, self.start.line, self.start.column)
+	}
+}
+
+impl Location {
+	/// Returns the span going from the start of this location to the end of
+	/// another one.
+	pub fn to(&self, other: &Location) -> Location {
+		Location {
+			path: self.path.to_owned(),
+			start: self.start,
+			end: other.end,
+		}
+	}
+}
+
 #[derive(Debug)]
 pub enum Token {
 	Word(String),
 	Symbol(Symbol),
+	/// A double-quoted string, its escape sequences already replaced.
+	String(String),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Symbol {
 	OpeningParenthesis,
 	ClosingParenthesis,
@@ -29,14 +62,39 @@ pub enum Symbol {
 	ClosingBracket,
 	Comma,
 	Colon,
+	Semicolon,
+	Equal,
+	Plus,
+	Minus,
+	Star,
+	Slash,
+	Percent,
+	Bang,
+	Less,
+	Greater,
+	Ampersand,
+	Pipe,
+	DoubleEqual,
+	BangEqual,
+	LessEqual,
+	GreaterEqual,
+	DoubleAmpersand,
+	DoublePipe,
+	FatArrow,
+	Dot,
 }
 
 pub struct Lexer {
 	path: String,
 	line: usize,
 	column: usize,
+	offset: usize,
 	word: Option<String>,
+	word_offset: usize,
 	in_comment: bool,
+	string: Option<String>,
+	string_start: Position,
+	in_escape: bool,
 }
 
 impl Lexer {
@@ -45,13 +103,22 @@ impl Lexer {
 			path: path.to_owned(),
 			line: 1,
 			column: 1,
+			offset: 0,
 			word: None,
+			word_offset: 0,
 			in_comment: false,
+			string: None,
+			string_start: Position::default(),
+			in_escape: false,
 		}
 	}
 
-	pub fn tokenize(&mut self, source: Chars) -> Vec<LocatedToken> {
+	/// Splits a source into tokens. Lexical errors do not stop the lexing:
+	/// what is invalid is reported and skipped, so the returned tokens hold
+	/// everything else for the parser to report its own errors on.
+	pub fn tokenize(&mut self, source: Chars) -> (Vec<LocatedToken>, Vec<Diagnostic>) {
 		let mut tokens = Vec::new();
+		let mut diagnostics = Vec::new();
 
 		for c in source {
 			if self.in_comment {
@@ -59,14 +126,87 @@ impl Lexer {
 					'\n' => {
 						self.in_comment = false;
 						self.line += 1;
-						self.column = 0;
+						self.column = 1;
 					}
 					_ => {
 						self.column += 1;
 					}
 				}
+			} else if let Some(string) = &mut self.string {
+				// A backslash does not carry a string over to the next line.
+				if c == '\n' {
+					diagnostics.push(self.unterminated_string());
+					self.string = None;
+					self.in_escape = false;
+					self.line += 1;
+					self.column = 1;
+				} else if self.in_escape {
+					self.in_escape = false;
+
+					match c {
+						'n' => string.push('\n'),
+						't' => string.push('\t'),
+						'r' => string.push('\r'),
+						'0' => string.push('\0'),
+						'\\' | '"' => string.push(c),
+						_ => diagnostics.push(
+							Diagnostic::error(
+								Code::InvalidEscape,
+								format!("unknown escape sequence \"\\{}\"", c),
+							)
+							.at(Location {
+								path: self.path.to_owned(),
+								start: Position {
+									offset: self.offset - 1,
+									line: self.line,
+									column: self.column - 1,
+								},
+								end: Position {
+									offset: self.offset + c.len_utf8(),
+									line: self.line,
+									column: self.column + 1,
+								},
+							})
+							.with_help("available escape sequences are \\n, \\t, \\r, \\0, \\\\ and \\\"".to_owned()),
+						),
+					}
+					self.column += 1;
+				} else {
+					match c {
+						'\\' => {
+							self.in_escape = true;
+							self.column += 1;
+						}
+						'"' => {
+							self.column += 1;
+							let string = self.string.take().unwrap();
+							tokens.push(LocatedToken {
+								location: self.string_location(self.offset + 1),
+								token: Token::String(string),
+							});
+						}
+						'\t' => {
+							string.push(c);
+							self.column += 4;
+						}
+						_ => {
+							string.push(c);
+							self.column += 1;
+						}
+					}
+				}
 			} else {
 				match c {
+					'"' => {
+						self.extend_word(&mut tokens);
+						self.string = Some(String::new());
+						self.string_start = Position {
+							offset: self.offset,
+							line: self.line,
+							column: self.column,
+						};
+						self.column += 1;
+					}
 					'(' => {
 						self.extend_word(&mut tokens);
 						tokens.push(self.wrap(Token::Symbol(Symbol::OpeningParenthesis)));
@@ -107,7 +247,40 @@ impl Lexer {
 						tokens.push(self.wrap(Token::Symbol(Symbol::Colon)));
 						self.column += 1;
 					}
+					';' => {
+						self.extend_word(&mut tokens);
+						tokens.push(self.wrap(Token::Symbol(Symbol::Semicolon)));
+						self.column += 1;
+					}
+					'=' => self.push_operator(&mut tokens, Symbol::Equal),
+					// Signs belong to the exponent of floats such as `2e-3`.
+					'+' | '-' if self.word.as_ref().is_some_and(|w| {
+						w.starts_with(|c: char| c.is_ascii_digit()) && w.ends_with(['e', 'E'])
+					}) =>
+					{
+						self.word.as_mut().unwrap().push(c);
+					}
+					// Dots belong to the fractional part of floats such as `1.5`.
+					'.' if self.word.as_ref().is_some_and(|w| w.starts_with(|c: char| c.is_ascii_digit())) => {
+						self.word.as_mut().unwrap().push(c);
+					}
+					'.' => {
+						self.extend_word(&mut tokens);
+						tokens.push(self.wrap(Token::Symbol(Symbol::Dot)));
+						self.column += 1;
+					}
+					'+' => self.push_operator(&mut tokens, Symbol::Plus),
+					'-' => self.push_operator(&mut tokens, Symbol::Minus),
+					'*' => self.push_operator(&mut tokens, Symbol::Star),
+					'/' => self.push_operator(&mut tokens, Symbol::Slash),
+					'%' => self.push_operator(&mut tokens, Symbol::Percent),
+					'!' => self.push_operator(&mut tokens, Symbol::Bang),
+					'<' => self.push_operator(&mut tokens, Symbol::Less),
+					'>' => self.push_operator(&mut tokens, Symbol::Greater),
+					'&' => self.push_operator(&mut tokens, Symbol::Ampersand),
+					'|' => self.push_operator(&mut tokens, Symbol::Pipe),
 					'#' => {
+						self.extend_word(&mut tokens);
 						self.in_comment = true;
 						self.column += 1;
 					}
@@ -120,8 +293,19 @@ impl Lexer {
 						self.extend_word(&mut tokens);
 						self.column += 4;
 					}
-					' ' => {
+					' ' | '\r' => {
+						self.extend_word(&mut tokens);
+						self.column += 1;
+					}
+					_ if c.is_control() => {
 						self.extend_word(&mut tokens);
+						diagnostics.push(
+							Diagnostic::error(
+								Code::UnexpectedCharacter,
+								format!("unexpected character {:?}", c),
+							)
+							.at(self.location(self.offset, self.offset + c.len_utf8(), 1)),
+						);
 						self.column += 1;
 					}
 					_ => {
@@ -129,31 +313,115 @@ impl Lexer {
 							w.push(c);
 						} else {
 							self.word = Some(String::from_iter([c]));
+							self.word_offset = self.offset;
 						}
 					}
 				}
 			}
+
+			self.offset += c.len_utf8();
 		}
 
-		tokens
+		self.extend_word(&mut tokens);
+
+		if self.string.take().is_some() {
+			diagnostics.push(self.unterminated_string());
+		}
+
+		(tokens, diagnostics)
 	}
 
 	fn extend_word(&mut self, tokens: &mut Vec<LocatedToken>) {
-		if let Some(w) = &self.word {
-			tokens.push(self.wrap(Token::Word(w.to_string())));
-			self.column += w.len();
-			self.word = None;
+		if let Some(w) = self.word.take() {
+			let width = w.chars().count();
+			tokens.push(LocatedToken {
+				location: self.location(self.word_offset, self.offset, width),
+				token: Token::Word(w),
+			});
+			self.column += width;
+		}
+	}
+
+	/// Pushes an operator symbol, merging it with the previous one when they
+	/// are adjacent and make up a two characters operator such as `<=`.
+	fn push_operator(&mut self, tokens: &mut Vec<LocatedToken>, symbol: Symbol) {
+		self.extend_word(tokens);
+
+		let merged = match tokens.last() {
+			Some(LocatedToken {
+				token: Token::Symbol(previous),
+				location,
+			}) if location.end.offset == self.offset => match (previous, symbol) {
+				(Symbol::Equal, Symbol::Equal) => Some(Symbol::DoubleEqual),
+				(Symbol::Equal, Symbol::Greater) => Some(Symbol::FatArrow),
+				(Symbol::Bang, Symbol::Equal) => Some(Symbol::BangEqual),
+				(Symbol::Less, Symbol::Equal) => Some(Symbol::LessEqual),
+				(Symbol::Greater, Symbol::Equal) => Some(Symbol::GreaterEqual),
+				(Symbol::Ampersand, Symbol::Ampersand) => Some(Symbol::DoubleAmpersand),
+				(Symbol::Pipe, Symbol::Pipe) => Some(Symbol::DoublePipe),
+				_ => None,
+			},
+			_ => None,
+		};
+
+		match merged {
+			Some(merged) => {
+				let previous = tokens.last_mut().unwrap();
+				previous.token = Token::Symbol(merged);
+				previous.location.end.offset += 1;
+				previous.location.end.column += 1;
+			}
+			None => tokens.push(self.wrap(Token::Symbol(symbol))),
 		}
+
+		self.column += 1;
 	}
 
+	/// Wraps a single character token found at the current position.
 	fn wrap(&self, token: Token) -> LocatedToken {
 		LocatedToken {
 			token,
-			location: Location {
-				path: self.path.to_owned(),
+			location: self.location(self.offset, self.offset + 1, 1),
+		}
+	}
+
+	/// Builds the location of the string being read, up to an offset on the
+	/// current line.
+	fn string_location(&self, end: usize) -> Location {
+		Location {
+			path: self.path.to_owned(),
+			start: self.string_start,
+			end: Position {
+				offset: end,
 				line: self.line,
 				column: self.column,
 			},
 		}
 	}
+
+	fn unterminated_string(&self) -> Diagnostic {
+		Diagnostic::error(
+			Code::UnterminatedString,
+			"unterminated string".to_owned(),
+		)
+		.at(self.string_location(self.offset))
+		.with_help("strings must be closed by a double quote on the line they start".to_owned())
+	}
+
+	/// Builds the location of a token starting at the current line and column.
+	fn location(&self, start: usize, end: usize, width: usize) -> Location {
+		Location {
+			path: self.path.to_owned(),
+			start: Position {
+				offset: start,
+				line: self.line,
+				column: self.column,
+			},
+			end: Position {
+				offset: end,
+				line: self.line,
+				column: self.column + width,
+			},
+		}
+	}
 }
@@ -1,4 +1,6 @@
 pub mod ast;
+pub mod diagnostics;
+pub mod emitting;
 pub mod formatting;
 pub mod graph;
 pub mod interpretation;
@@ -6,5 +8,9 @@ pub mod lexing;
 pub mod native;
 pub mod parsing;
 pub mod reading;
+pub mod rendering;
+pub mod resolving;
+pub mod typing;
 pub mod validation;
+pub mod value;
 pub mod visualization;
@@ -1,16 +1,20 @@
-use std::error::Error;
-use std::io::Write;
+use std::io::{IsTerminal, Write};
 
 use clap::{App, Arg, ArgMatches};
 
 use opentelemetry::global::shutdown_tracer_provider;
 use opentelemetry_datadog::{new_pipeline, ApiVersion};
 
+use idem::ast::AST;
+use idem::diagnostics::{has_errors, Code, Diagnostic};
+use idem::emitting::{Emitter, MessageFormat};
 use idem::formatting::Formattable;
 use idem::interpretation::Interpreter;
 use idem::lexing::Lexer;
 use idem::parsing::Parser;
-use idem::reading::read;
+use idem::reading::Sources;
+use idem::resolving::resolve;
+use idem::typing::check;
 use idem::validation::validate;
 use idem::visualization;
 
@@ -32,6 +36,20 @@ fn main() {
                     Arg::new("tracing")
                     .about("activate open telemetry tracing")
                     .takes_value(false)
+                )
+                .arg(
+                    Arg::new("message-format")
+                        .long("message-format")
+                        .about("Format in which diagnostics are reported")
+                        .takes_value(true)
+                        .possible_values(["human", "json", "sarif"])
+                        .default_value("human"),
+                )
+                .arg(
+                    Arg::new("ARGS")
+                        .about("Arguments passed to main, after `--`")
+                        .multiple_values(true)
+                        .last(true),
                 ),
         )
         .subcommand(
@@ -42,6 +60,14 @@ fn main() {
                     Arg::new("PATH")
                         .about("Path to an Idem source file")
                         .required(true),
+                )
+                .arg(
+                    Arg::new("message-format")
+                        .long("message-format")
+                        .about("Format in which diagnostics are reported")
+                        .takes_value(true)
+                        .possible_values(["human", "json", "sarif"])
+                        .default_value("human"),
                 ),
         )
         .subcommand(
@@ -52,6 +78,14 @@ fn main() {
                     Arg::new("PATH")
                         .about("Path to an Idem source file")
                         .required(true),
+                )
+                .arg(
+                    Arg::new("message-format")
+                        .long("message-format")
+                        .about("Format in which diagnostics are reported")
+                        .takes_value(true)
+                        .possible_values(["human", "json", "sarif"])
+                        .default_value("human"),
                 ),
         )
         .subcommand(
@@ -76,118 +110,258 @@ fn main() {
                 ),
         );
 
-    print_if_error(match app.get_matches().subcommand() {
-        Some(("run", matches)) => run_cmd(matches),
-        Some(("format", matches)) => format_cmd(matches),
-        Some(("validate", matches)) => validate_cmd(matches),
+    let mut sources = Sources::new();
+
+    let status = match app.get_matches().subcommand() {
+        Some(("run", matches)) => {
+            // The standard output belongs to the program being run.
+            let mut emitter = emitter(matches, Stream::Stderr, Stream::Stderr);
+            report(&mut sources, &mut emitter, matches, run_cmd)
+        }
+        Some(("format", matches)) => {
+            let mut emitter = emitter(matches, Stream::Stderr, Stream::Stdout);
+            report(&mut sources, &mut emitter, matches, format_cmd)
+        }
+        Some(("validate", matches)) => {
+            let mut emitter = emitter(matches, Stream::Stdout, Stream::Stdout);
+            report(&mut sources, &mut emitter, matches, validate_cmd)
+        }
         Some(("display", matches)) => match matches.subcommand() {
-            Some(("functions", sub_matches)) => display_functions_cmd(sub_matches),
-            _ => Ok(()),
+            Some(("functions", sub_matches)) => {
+                let mut emitter = Emitter::new(
+                    MessageFormat::Human,
+                    Box::new(std::io::stderr()),
+                    std::io::stderr().is_terminal(),
+                );
+                report(
+                    &mut sources,
+                    &mut emitter,
+                    sub_matches,
+                    display_functions_cmd,
+                )
+            }
+            _ => 0,
         },
-        _ => Ok(()),
-    })
+        _ => 0,
+    };
+
+    if status != 0 {
+        std::process::exit(status);
+    }
+}
+
+enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// Builds the emitter for a subcommand, writing human readable diagnostics
+/// and machine readable ones to their respective streams.
+fn emitter(matches: &ArgMatches, human: Stream, machine: Stream) -> Emitter {
+    let format = matches
+        .value_of_t::<MessageFormat>("message-format")
+        .unwrap_or(MessageFormat::Human);
+
+    let stream = match format {
+        MessageFormat::Human => human,
+        MessageFormat::Json | MessageFormat::Sarif => machine,
+    };
+    let colored = format == MessageFormat::Human;
+
+    match stream {
+        Stream::Stdout => Emitter::new(
+            format,
+            Box::new(std::io::stdout()),
+            colored && std::io::stdout().is_terminal(),
+        ),
+        Stream::Stderr => Emitter::new(
+            format,
+            Box::new(std::io::stderr()),
+            colored && std::io::stderr().is_terminal(),
+        ),
+    }
 }
 
-fn print_if_error(result: Result<(), Box<dyn Error>>) {
-    if let Err(err) = result {
-        eprintln!("{}", err);
-        std::process::exit(1);
+/// Runs a subcommand, emits the diagnostics it produced and returns the exit
+/// status of the process.
+fn report(
+    sources: &mut Sources,
+    emitter: &mut Emitter,
+    matches: &ArgMatches,
+    cmd: fn(&mut Sources, &mut Emitter, &ArgMatches) -> Result<i32, Vec<Diagnostic>>,
+) -> i32 {
+    let status = match cmd(sources, emitter, matches) {
+        Ok(status) => status,
+        Err(diagnostics) => {
+            emitter.emit(sources, &diagnostics);
+            1
+        }
+    };
+
+    emitter.finish();
+
+    if emitter.has_errors() {
+        1
+    } else {
+        status
     }
 }
 
-fn run_cmd(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
-    let path = matches.value_of_t::<String>("PATH")?;
-    let mut lexer = Lexer::new(&path);
-    let tokens = lexer.tokenize(read(&path)?.chars());
+/// Parses a file, returning the lexical and syntax errors along with the AST
+/// the parser recovered from them.
+fn parse_with_errors(
+    sources: &mut Sources,
+    path: &str,
+) -> Result<(AST, Vec<Diagnostic>), Vec<Diagnostic>> {
+    let mut lexer = Lexer::new(path);
+    let (tokens, mut diagnostics) =
+        lexer.tokenize(sources.read(path).map_err(|err| vec![err])?.chars());
     let mut tokens_stream = tokens.iter();
 
     let mut parser = Parser::new(&mut tokens_stream);
-    let ast = parser.parse()?;
+    let (ast, mut syntax_errors) = parser.parse();
+    diagnostics.append(&mut syntax_errors);
 
-    let reports = validate(&ast);
-    if !reports.is_empty() {
-        eprintln!("Issues were found in {}:", path);
+    Ok((ast, diagnostics))
+}
 
-        for report in reports {
-            println!("{}", report);
-        }
+fn parse(sources: &mut Sources, path: &str) -> Result<AST, Vec<Diagnostic>> {
+    let (ast, diagnostics) = parse_with_errors(sources, path)?;
 
-        std::process::exit(1);
+    if diagnostics.is_empty() {
+        Ok(ast)
+    } else {
+        Err(diagnostics)
     }
+}
 
-    let mut interpreter = Interpreter {};
+/// Loads a program along with the modules it imports.
+fn load(sources: &mut Sources, path: &str) -> Result<AST, Vec<Diagnostic>> {
+    let (ast, diagnostics) = resolve(sources, path);
+
+    if diagnostics.is_empty() {
+        Ok(ast)
+    } else {
+        Err(diagnostics)
+    }
+}
+
+fn io_error(path: &str, err: std::io::Error) -> Vec<Diagnostic> {
+    vec![Diagnostic::error(
+        Code::Io,
+        format!("could not write {}: {}", path, err),
+    )]
+}
+
+fn run_cmd(
+    sources: &mut Sources,
+    emitter: &mut Emitter,
+    matches: &ArgMatches,
+) -> Result<i32, Vec<Diagnostic>> {
+    let path = matches.value_of("PATH").unwrap();
+    let arguments: Vec<String> = matches
+        .values_of("ARGS")
+        .map(|values| values.map(str::to_owned).collect())
+        .unwrap_or_default();
+    let ast = load(sources, path)?;
+
+    let mut diagnostics = validate(&ast);
+    diagnostics.append(&mut check(&ast));
+    if has_errors(&diagnostics) {
+        return Err(diagnostics);
+    }
+    emitter.emit(sources, &diagnostics);
+
+    let mut interpreter = Interpreter::default();
 
     if matches.is_present("tracing") {
         let _tracer = new_pipeline()
             .with_service_name(path)
             .with_version(ApiVersion::Version05)
-            .install_simple()?;
+            .install_simple()
+            .map_err(|err| {
+                vec![Diagnostic::error(
+                    Code::Tracing,
+                    format!("could not install tracing: {}", err),
+                )]
+            })?;
     }
 
-    interpreter.run(&ast)?;
+    let status = interpreter.run(&ast, &arguments).map_err(|err| vec![err])?;
 
     if matches.is_present("tracing") {
         shutdown_tracer_provider();
     }
 
-    Ok(())
+    Ok(status)
 }
 
-fn format_cmd(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
-    let path = matches.value_of_t::<String>("PATH")?;
-    let mut lexer = Lexer::new(&path);
-    let tokens = lexer.tokenize(read(&path)?.chars());
-    let mut tokens_stream = tokens.iter();
-
-    let mut parser = Parser::new(&mut tokens_stream);
-    let ast = parser.parse()?;
+fn format_cmd(
+    sources: &mut Sources,
+    _emitter: &mut Emitter,
+    matches: &ArgMatches,
+) -> Result<i32, Vec<Diagnostic>> {
+    let path = matches.value_of("PATH").unwrap();
+    let ast = parse(sources, path)?;
 
     let mut f = std::fs::OpenOptions::new()
         .write(true)
         .truncate(true)
-        .open(path)?;
+        .open(path)
+        .map_err(|err| io_error(path, err))?;
 
-    f.write_all(format!("{}\n", ast.format()).as_bytes())?;
-    f.flush()?;
+    f.write_all(format!("{}\n", ast.format()).as_bytes())
+        .map_err(|err| io_error(path, err))?;
+    f.flush().map_err(|err| io_error(path, err))?;
 
-    Ok(())
+    Ok(0)
 }
 
-fn validate_cmd(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
-    let path = matches.value_of_t::<String>("PATH")?;
-    let mut lexer = Lexer::new(&path);
-    let tokens = lexer.tokenize(read(&path)?.chars());
-    let mut tokens_stream = tokens.iter();
+fn validate_cmd(
+    sources: &mut Sources,
+    emitter: &mut Emitter,
+    matches: &ArgMatches,
+) -> Result<i32, Vec<Diagnostic>> {
+    let path = matches.value_of("PATH").unwrap();
+    let (ast, diagnostics) = resolve(sources, path);
 
-    let mut parser = Parser::new(&mut tokens_stream);
-    let ast = parser.parse()?;
+    // Syntax errors come first, as they often explain the others.
+    emitter.emit(sources, &diagnostics);
 
-    let reports = validate(&ast);
-    for report in reports {
-        println!("{}", report);
+    // Types are only checked on complete programs, as missing pieces of code
+    // would be reported as mismatches. For the same reason, what looks unused
+    // may well be used by what could not be parsed.
+    if diagnostics.is_empty() {
+        emitter.emit(sources, &validate(&ast));
+        emitter.emit(sources, &check(&ast));
+    } else {
+        let errors: Vec<Diagnostic> = validate(&ast)
+            .into_iter()
+            .filter(|diagnostic| diagnostic.is_error())
+            .collect();
+        emitter.emit(sources, &errors);
     }
 
-    Ok(())
+    Ok(0)
 }
 
-fn display_functions_cmd(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
-    let path = matches.value_of_t::<String>("PATH")?;
-
-    let mut lexer = Lexer::new(&path);
-    let tokens = lexer.tokenize(read(&path)?.chars());
-    let mut tokens_stream = tokens.iter();
-
-    let mut parser = Parser::new(&mut tokens_stream);
-    let ast = parser.parse()?;
+fn display_functions_cmd(
+    sources: &mut Sources,
+    _emitter: &mut Emitter,
+    matches: &ArgMatches,
+) -> Result<i32, Vec<Diagnostic>> {
+    let path = matches.value_of("PATH").unwrap();
+    let ast = load(sources, path)?;
 
-    let graph = visualization::function_calls_graph(&path, &ast);
+    let graph = visualization::function_calls_graph(path, &ast);
 
-    if let Ok(output) = matches.value_of_t::<String>("output") {
-        let mut file = std::fs::File::create(output)?;
-        file.write_all(format!("{}", graph).as_bytes())?;
+    if let Some(output) = matches.value_of("output") {
+        let mut file = std::fs::File::create(output).map_err(|err| io_error(output, err))?;
+        file.write_all(format!("{}", graph).as_bytes())
+            .map_err(|err| io_error(output, err))?;
     } else {
         println!("{}", graph);
     }
 
-    Ok(())
+    Ok(0)
 }
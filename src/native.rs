@@ -1,216 +1,493 @@
 use std::collections::HashMap;
+use std::io::{BufRead, Write};
 
-use super::ast::VariableDefinition;
-use super::lexing::Location;
+use super::diagnostics::{Code, Diagnostic};
+use super::typing::{Signature, Type};
+use super::value::Value;
 
-pub fn or(a: u32, b: u32) -> u32 {
-    a | b
+pub fn or(a: bool, b: bool) -> bool {
+    a || b
 }
 
-pub fn and(a: u32, b: u32) -> u32 {
-    a & b
+pub fn and(a: bool, b: bool) -> bool {
+    a && b
 }
 
-pub fn xor(a: u32, b: u32) -> u32 {
+pub fn xor(a: bool, b: bool) -> bool {
     a ^ b
 }
 
-pub fn not(a: u32) -> u32 {
+pub fn not(a: bool) -> bool {
     !a
 }
 
-pub fn equal(a: u32, b: u32) -> u32 {
-    (a == b) as u32
+pub fn equal(a: &Value, b: &Value) -> bool {
+    a == b
 }
 
-pub fn add(a: u32, b: u32) -> u32 {
+pub fn not_equal(a: &Value, b: &Value) -> bool {
+    a != b
+}
+
+pub fn add(a: i64, b: i64) -> Result<i64, Diagnostic> {
+    a.checked_add(b).ok_or_else(|| overflow("add", a, b))
+}
+
+pub fn sub(a: i64, b: i64) -> Result<i64, Diagnostic> {
+    a.checked_sub(b).ok_or_else(|| overflow("subtract", a, b))
+}
+
+pub fn multiply(a: i64, b: i64) -> Result<i64, Diagnostic> {
+    a.checked_mul(b).ok_or_else(|| overflow("multiply", a, b))
+}
+
+pub fn negate(a: i64) -> Result<i64, Diagnostic> {
+    a.checked_neg().ok_or_else(|| {
+        Diagnostic::error(
+            Code::IntegerOverflow,
+            format!("attempt to negate {} with overflow", a),
+        )
+        .with_help(format!("integers range from {} to {}", i64::MIN, i64::MAX))
+    })
+}
+
+pub fn add_floats(a: f64, b: f64) -> f64 {
     a + b
 }
 
-pub fn sub(a: u32, b: u32) -> u32 {
+pub fn sub_floats(a: f64, b: f64) -> f64 {
     a - b
 }
 
-pub fn multiply(a: u32, b: u32) -> u32 {
+pub fn multiply_floats(a: f64, b: f64) -> f64 {
     a * b
 }
 
-pub fn print(a: u32) {
-    println!("{}", a)
+pub fn negate_float(a: f64) -> f64 {
+    -a
+}
+
+/// Divides two integers, rounding the quotient towards zero.
+pub fn divide(a: i64, b: i64) -> Result<i64, Diagnostic> {
+    if b == 0 {
+        return Err(division_by_zero());
+    }
+
+    a.checked_div(b).ok_or_else(|| overflow("divide", a, b))
+}
+
+/// The remainder of the division of two integers, which has the sign of the
+/// dividend.
+pub fn modulo(a: i64, b: i64) -> Result<i64, Diagnostic> {
+    if b == 0 {
+        return Err(division_by_zero());
+    }
+
+    a.checked_rem(b).ok_or_else(|| overflow("modulo", a, b))
+}
+
+pub fn divide_floats(a: f64, b: f64) -> Result<f64, Diagnostic> {
+    if b == 0.0 {
+        return Err(division_by_zero());
+    }
+
+    Ok(a / b)
+}
+
+pub fn modulo_floats(a: f64, b: f64) -> Result<f64, Diagnostic> {
+    if b == 0.0 {
+        return Err(division_by_zero());
+    }
+
+    Ok(a % b)
+}
+
+fn division_by_zero() -> Diagnostic {
+    Diagnostic::error(Code::DivisionByZero, "attempt to divide by zero".to_owned())
+}
+
+pub fn less<T: PartialOrd>(a: T, b: T) -> bool {
+    a < b
+}
+
+pub fn greater<T: PartialOrd>(a: T, b: T) -> bool {
+    a > b
+}
+
+pub fn less_equal<T: PartialOrd>(a: T, b: T) -> bool {
+    a <= b
+}
+
+pub fn greater_equal<T: PartialOrd>(a: T, b: T) -> bool {
+    a >= b
+}
+
+pub fn to_float(a: i64) -> f64 {
+    a as f64
+}
+
+/// Converts a float to an integer, dropping its fractional part.
+pub fn to_int(a: f64) -> Result<i64, Diagnostic> {
+    let truncated = a.trunc();
+
+    if truncated >= i64::MIN as f64 && truncated < i64::MAX as f64 {
+        Ok(truncated as i64)
+    } else {
+        Err(Diagnostic::error(
+            Code::InvalidConversion,
+            format!("{:?} cannot be converted to an integer", a),
+        ))
+    }
+}
+
+fn overflow(operation: &str, a: i64, b: i64) -> Diagnostic {
+    Diagnostic::error(
+        Code::IntegerOverflow,
+        format!("attempt to {} {} and {} with overflow", operation, a, b),
+    )
+    .with_help(format!("integers range from {} to {}", i64::MIN, i64::MAX))
+}
+
+pub fn length(list: &[Value]) -> i64 {
+    list.len() as i64
+}
+
+pub fn get<'a>(list: &[Value<'a>], index: i64) -> Result<Value<'a>, Diagnostic> {
+    let element = usize::try_from(index)
+        .ok()
+        .and_then(|index| list.get(index));
+
+    element.cloned().ok_or_else(|| {
+        Diagnostic::error(
+            Code::IndexOutOfBounds,
+            format!(
+                "index {} is out of bounds for a list of length {}",
+                index,
+                list.len()
+            ),
+        )
+    })
+}
+
+pub fn push<'a>(list: &[Value<'a>], element: Value<'a>) -> Vec<Value<'a>> {
+    let mut list = list.to_vec();
+    list.push(element);
+    list
+}
+
+pub fn string_length(string: &str) -> i64 {
+    string.chars().count() as i64
+}
+
+pub fn concat(a: &str, b: &str) -> String {
+    format!("{}{}", a, b)
+}
+
+pub fn head<'a>(list: &[Value<'a>]) -> Result<Value<'a>, Diagnostic> {
+    list.first().cloned().ok_or_else(empty_list)
+}
+
+pub fn tail<'a>(list: &[Value<'a>]) -> Result<Vec<Value<'a>>, Diagnostic> {
+    match list.split_first() {
+        Some((_, tail)) => Ok(tail.to_vec()),
+        None => Err(empty_list()),
+    }
+}
+
+/// Applies a function to every element of a list, in order.
+pub fn map<'a>(
+    list: &[Value<'a>],
+    apply: impl FnMut(Value<'a>) -> Result<Value<'a>, Diagnostic>,
+) -> Result<Vec<Value<'a>>, Diagnostic> {
+    list.iter().cloned().map(apply).collect()
+}
+
+/// Keeps the elements of a list for which a function returns `true`.
+pub fn filter<'a>(
+    list: &[Value<'a>],
+    mut keep: impl FnMut(Value<'a>) -> Result<bool, Diagnostic>,
+) -> Result<Vec<Value<'a>>, Diagnostic> {
+    let mut kept = Vec::new();
+
+    for element in list {
+        if keep(element.clone())? {
+            kept.push(element.clone());
+        }
+    }
+
+    Ok(kept)
+}
+
+/// Combines the elements of a list from the first to the last, starting
+/// from an initial value.
+pub fn fold<'a>(
+    list: &[Value<'a>],
+    initial: Value<'a>,
+    mut combine: impl FnMut(Value<'a>, Value<'a>) -> Result<Value<'a>, Diagnostic>,
+) -> Result<Value<'a>, Diagnostic> {
+    let mut accumulator = initial;
+
+    for element in list {
+        accumulator = combine(accumulator, element.clone())?;
+    }
+
+    Ok(accumulator)
+}
+
+fn empty_list() -> Diagnostic {
+    Diagnostic::error(Code::EmptyList, "the list is empty".to_owned())
+}
+
+pub fn print(output: &mut dyn Write, a: &Value) -> Result<(), Diagnostic> {
+    writeln!(output, "{}", a).map_err(output_error)
 }
 
-pub fn native_functions_map<'a>() -> HashMap<&'a str, Vec<VariableDefinition>> {
-    let mut map = HashMap::<&'a str, Vec<VariableDefinition>>::new();
+/// Reads a line of input, without its line ending.
+pub fn read_line(input: &mut dyn BufRead) -> Result<String, Diagnostic> {
+    let mut line = String::new();
+
+    match input.read_line(&mut line) {
+        Ok(0) => Err(Diagnostic::error(
+            Code::EndOfInput,
+            "there is no more input to read".to_owned(),
+        )
+        .with_help("check `end_of_input()` before reading".to_owned())),
+        Ok(_) => {
+            if line.ends_with('\n') {
+                line.pop();
+                if line.ends_with('\r') {
+                    line.pop();
+                }
+            }
+
+            Ok(line)
+        }
+        Err(err) => Err(input_error(err)),
+    }
+}
+
+/// Reads a line of input holding an integer.
+pub fn read_number(input: &mut dyn BufRead) -> Result<i64, Diagnostic> {
+    let line = read_line(input)?;
+
+    line.trim().parse().map_err(|_| {
+        Diagnostic::error(
+            Code::InvalidConversion,
+            format!("{:?} cannot be converted to an integer", line),
+        )
+    })
+}
+
+/// Tells whether all the input has been read.
+pub fn end_of_input(input: &mut dyn BufRead) -> Result<bool, Diagnostic> {
+    input
+        .fill_buf()
+        .map(|buffer| buffer.is_empty())
+        .map_err(input_error)
+}
+
+fn input_error(err: std::io::Error) -> Diagnostic {
+    Diagnostic::error(Code::Io, format!("could not read input: {}", err))
+}
+
+pub fn output_error(err: std::io::Error) -> Diagnostic {
+    Diagnostic::error(Code::Io, format!("could not write output: {}", err))
+}
+
+/// The native functions reading input or writing output, which constants
+/// cannot call as they are evaluated before `main`.
+pub const INPUT_OUTPUT: [&str; 4] = ["print", "read_line", "read_number", "end_of_input"];
+
+/// The signatures of every native function. Overloaded functions have
+/// several, tried in order when typing a call.
+pub fn native_functions_map<'a>() -> HashMap<&'a str, Vec<Signature>> {
+    let mut map = HashMap::<&'a str, Vec<Signature>>::new();
 
     map.insert(
         "or",
+        vec![Signature::new(vec![Type::Bool, Type::Bool], Type::Bool)],
+    );
+    map.insert(
+        "and",
+        vec![Signature::new(vec![Type::Bool, Type::Bool], Type::Bool)],
+    );
+    map.insert(
+        "xor",
+        vec![Signature::new(vec![Type::Bool, Type::Bool], Type::Bool)],
+    );
+    map.insert("not", vec![Signature::new(vec![Type::Bool], Type::Bool)]);
+    map.insert(
+        "equal",
+        vec![Signature::new(
+            vec![Type::Parameter(0), Type::Parameter(0)],
+            Type::Bool,
+        )],
+    );
+    map.insert(
+        "not_equal",
+        vec![Signature::new(
+            vec![Type::Parameter(0), Type::Parameter(0)],
+            Type::Bool,
+        )],
+    );
+    map.insert(
+        "negate",
         vec![
-            VariableDefinition {
-                name: String::from("a"),
-                location: Location {
-                    path: String::new(),
-                    line: 0,
-                    column: 0,
-                },
-            },
-            VariableDefinition {
-                name: String::from("b"),
-                location: Location {
-                    path: String::new(),
-                    line: 0,
-                    column: 0,
-                },
-            },
+            Signature::new(vec![Type::Int], Type::Int),
+            Signature::new(vec![Type::Float], Type::Float),
         ],
     );
     map.insert(
-        "and",
+        "add",
         vec![
-            VariableDefinition {
-                name: String::from("a"),
-                location: Location {
-                    path: String::new(),
-                    line: 0,
-                    column: 0,
-                },
-            },
-            VariableDefinition {
-                name: String::from("b"),
-                location: Location {
-                    path: String::new(),
-                    line: 0,
-                    column: 0,
-                },
-            },
+            Signature::new(vec![Type::Int, Type::Int], Type::Int),
+            Signature::new(vec![Type::Float, Type::Float], Type::Float),
         ],
     );
     map.insert(
-        "xor",
+        "sub",
+        vec![
+            Signature::new(vec![Type::Int, Type::Int], Type::Int),
+            Signature::new(vec![Type::Float, Type::Float], Type::Float),
+        ],
+    );
+    map.insert(
+        "multiply",
         vec![
-            VariableDefinition {
-                name: String::from("a"),
-                location: Location {
-                    path: String::new(),
-                    line: 0,
-                    column: 0,
-                },
-            },
-            VariableDefinition {
-                name: String::from("b"),
-                location: Location {
-                    path: String::new(),
-                    line: 0,
-                    column: 0,
-                },
-            },
+            Signature::new(vec![Type::Int, Type::Int], Type::Int),
+            Signature::new(vec![Type::Float, Type::Float], Type::Float),
         ],
     );
     map.insert(
-        "not",
-        vec![VariableDefinition {
-            name: String::from("a"),
-            location: Location {
-                path: String::new(),
-                line: 0,
-                column: 0,
-            },
-        }],
+        "divide",
+        vec![
+            Signature::new(vec![Type::Int, Type::Int], Type::Int),
+            Signature::new(vec![Type::Float, Type::Float], Type::Float),
+        ],
     );
     map.insert(
-        "equal",
+        "modulo",
         vec![
-            VariableDefinition {
-                name: String::from("a"),
-                location: Location {
-                    path: String::new(),
-                    line: 0,
-                    column: 0,
-                },
-            },
-            VariableDefinition {
-                name: String::from("b"),
-                location: Location {
-                    path: String::new(),
-                    line: 0,
-                    column: 0,
-                },
-            },
+            Signature::new(vec![Type::Int, Type::Int], Type::Int),
+            Signature::new(vec![Type::Float, Type::Float], Type::Float),
         ],
     );
     map.insert(
-        "add",
+        "less",
         vec![
-            VariableDefinition {
-                name: String::from("a"),
-                location: Location {
-                    path: String::new(),
-                    line: 0,
-                    column: 0,
-                },
-            },
-            VariableDefinition {
-                name: String::from("b"),
-                location: Location {
-                    path: String::new(),
-                    line: 0,
-                    column: 0,
-                },
-            },
+            Signature::new(vec![Type::Int, Type::Int], Type::Bool),
+            Signature::new(vec![Type::Float, Type::Float], Type::Bool),
         ],
     );
     map.insert(
-        "sub",
+        "greater",
         vec![
-            VariableDefinition {
-                name: String::from("a"),
-                location: Location {
-                    path: String::new(),
-                    line: 0,
-                    column: 0,
-                },
-            },
-            VariableDefinition {
-                name: String::from("b"),
-                location: Location {
-                    path: String::new(),
-                    line: 0,
-                    column: 0,
-                },
-            },
+            Signature::new(vec![Type::Int, Type::Int], Type::Bool),
+            Signature::new(vec![Type::Float, Type::Float], Type::Bool),
         ],
     );
     map.insert(
-        "multiply",
+        "less_equal",
+        vec![
+            Signature::new(vec![Type::Int, Type::Int], Type::Bool),
+            Signature::new(vec![Type::Float, Type::Float], Type::Bool),
+        ],
+    );
+    map.insert(
+        "greater_equal",
+        vec![
+            Signature::new(vec![Type::Int, Type::Int], Type::Bool),
+            Signature::new(vec![Type::Float, Type::Float], Type::Bool),
+        ],
+    );
+    map.insert(
+        "to_float",
+        vec![Signature::new(vec![Type::Int], Type::Float)],
+    );
+    map.insert("to_int", vec![Signature::new(vec![Type::Float], Type::Int)]);
+    map.insert(
+        "length",
         vec![
-            VariableDefinition {
-                name: String::from("a"),
-                location: Location {
-                    path: String::new(),
-                    line: 0,
-                    column: 0,
-                },
-            },
-            VariableDefinition {
-                name: String::from("b"),
-                location: Location {
-                    path: String::new(),
-                    line: 0,
-                    column: 0,
-                },
-            },
+            Signature::new(vec![Type::list(Type::Parameter(0))], Type::Int),
+            Signature::new(vec![Type::String], Type::Int),
         ],
     );
+    map.insert(
+        "get",
+        vec![Signature::new(
+            vec![Type::list(Type::Parameter(0)), Type::Int],
+            Type::Parameter(0),
+        )],
+    );
+    map.insert(
+        "push",
+        vec![Signature::new(
+            vec![Type::list(Type::Parameter(0)), Type::Parameter(0)],
+            Type::list(Type::Parameter(0)),
+        )],
+    );
+    map.insert(
+        "head",
+        vec![Signature::new(
+            vec![Type::list(Type::Parameter(0))],
+            Type::Parameter(0),
+        )],
+    );
+    map.insert(
+        "tail",
+        vec![Signature::new(
+            vec![Type::list(Type::Parameter(0))],
+            Type::list(Type::Parameter(0)),
+        )],
+    );
+    map.insert(
+        "map",
+        vec![Signature::new(
+            vec![
+                Type::list(Type::Parameter(0)),
+                Type::function(vec![Type::Parameter(0)], Type::Parameter(1)),
+            ],
+            Type::list(Type::Parameter(1)),
+        )],
+    );
+    map.insert(
+        "filter",
+        vec![Signature::new(
+            vec![
+                Type::list(Type::Parameter(0)),
+                Type::function(vec![Type::Parameter(0)], Type::Bool),
+            ],
+            Type::list(Type::Parameter(0)),
+        )],
+    );
+    map.insert(
+        "fold",
+        vec![Signature::new(
+            vec![
+                Type::list(Type::Parameter(0)),
+                Type::Parameter(1),
+                Type::function(
+                    vec![Type::Parameter(1), Type::Parameter(0)],
+                    Type::Parameter(1),
+                ),
+            ],
+            Type::Parameter(1),
+        )],
+    );
+    map.insert(
+        "concat",
+        vec![Signature::new(
+            vec![Type::String, Type::String],
+            Type::String,
+        )],
+    );
     map.insert(
         "print",
-        vec![VariableDefinition {
-            name: String::from("a"),
-            location: Location {
-                path: String::new(),
-                line: 0,
-                column: 0,
-            },
-        }],
+        vec![Signature::new(vec![Type::Parameter(0)], Type::Unit)],
     );
+    map.insert("read_line", vec![Signature::new(vec![], Type::String)]);
+    map.insert("read_number", vec![Signature::new(vec![], Type::Int)]);
+    map.insert("end_of_input", vec![Signature::new(vec![], Type::Bool)]);
 
     map
 }
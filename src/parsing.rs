@@ -1,291 +1,898 @@
-use std::error::Error;
-
 use super::ast::{
-    Branch, Expression, FunctionCall, FunctionDefinition, FunctionPrototype, Literal, Number,
-    Statement, Variable, VariableDefinition, AST,
+    Arm, Block, Bool, Branch, ConstantDefinition, Expression, FieldAccess, FieldDefinition,
+    FieldValue, Float, FunctionCall, FunctionDefinition, FunctionPrototype, Import, Lambda, Let,
+    List, ListType, Literal, Match, NamedType, Number, Operator, Pattern, Record, RecordDefinition,
+    Statement, StringLiteral, TypeAnnotation, Variable, VariableDefinition, AST,
 };
+use super::diagnostics::{Code, Diagnostic};
 use super::lexing::{LocatedToken, Location, Symbol, Token};
 
 pub struct Parser<'a, I: Iterator<Item = &'a LocatedToken>> {
     tokens: &'a mut I,
     current_token: Option<&'a LocatedToken>,
+    previous_token: Option<&'a LocatedToken>,
+    /// How many braces are currently open.
+    depth: usize,
+    diagnostics: Vec<Diagnostic>,
 }
 
-#[derive(Debug)]
-pub struct SyntaxError {
-    location: Location,
-    issue: String,
-}
-
-impl std::fmt::Display for SyntaxError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(
-            f,
-            "{}:{}:{} {}",
-            self.location.path, self.location.line, self.location.column, self.issue
-        )
-    }
-}
-
-impl Error for SyntaxError {}
-
 impl<'a, I: Iterator<Item = &'a LocatedToken>> Parser<'a, I> {
     pub fn new(tokens: &'a mut I) -> Self {
         Self {
             current_token: tokens.next(),
             tokens,
+            previous_token: None,
+            depth: 0,
+            diagnostics: Vec::new(),
         }
     }
 
-    pub fn parse(&mut self) -> Result<AST, Box<dyn Error>> {
+    /// Parses the whole tokens stream. Syntax errors do not stop the parsing:
+    /// the parser skips to the end of the enclosing block or function and goes
+    /// on, so the returned AST holds everything it could make sense of.
+    pub fn parse(&mut self) -> (AST, Vec<Diagnostic>) {
         let mut ast = AST {
             statements: Vec::new(),
         };
 
         while self.current_token.is_some() {
-            ast.statements.push(self.parse_statement()?);
+            match self.parse_statement() {
+                Ok(statement) => ast.statements.push(statement),
+                Err(diagnostic) => {
+                    self.report(diagnostic);
+                    self.synchronize(0);
+                }
+            }
         }
 
-        Ok(ast)
+        (ast, std::mem::take(&mut self.diagnostics))
     }
 
-    fn parse_statement(&mut self) -> Result<Statement, Box<dyn Error>> {
-        Ok(Statement::FunctionDefinition(
-            self.parse_function_definition()?,
-        ))
+    fn parse_statement(&mut self) -> Result<Statement, Diagnostic> {
+        if self.is_word("import") {
+            Ok(Statement::Import(self.parse_import()?))
+        } else if self.is_word("const") {
+            Ok(Statement::ConstantDefinition(Box::new(
+                self.parse_constant_definition()?,
+            )))
+        } else if self.is_word("record") {
+            Ok(Statement::RecordDefinition(self.parse_record_definition()?))
+        } else {
+            Ok(Statement::FunctionDefinition(Box::new(
+                self.parse_function_definition()?,
+            )))
+        }
     }
 
-    fn parse_function_definition(&mut self) -> Result<FunctionDefinition, Box<dyn Error>> {
-        Ok(FunctionDefinition {
-            location: self.current_token.unwrap().location.clone(),
-            prototype: self.parse_function_prototype()?,
-            body: self.parse_function_body()?,
+    fn parse_import(&mut self) -> Result<Import, Diagnostic> {
+        let start = self.current("an import")?.location.clone();
+        self.next_token();
+
+        let Token::String(path) = &self.current("the path of a module")?.token else {
+            return Err(self.syntax_error("the path of a module"));
+        };
+        self.next_token();
+
+        Ok(Import {
+            path: path.clone(),
+            location: self.span(&start),
+        })
+    }
+
+    fn parse_constant_definition(&mut self) -> Result<ConstantDefinition, Diagnostic> {
+        let start = self.current("a constant definition")?.location.clone();
+        self.next_token();
+
+        let variable = self.parse_variable_definition()?;
+        self.expect(Symbol::Equal, "an equal sign")?;
+        let value = self.parse_expression()?;
+
+        Ok(ConstantDefinition {
+            variable,
+            value,
+            location: self.span(&start),
         })
     }
 
-    fn parse_function_prototype(&mut self) -> Result<FunctionPrototype, Box<dyn Error>> {
-        if let Token::Word(name) = &self.current_token.unwrap().token {
+    fn parse_record_definition(&mut self) -> Result<RecordDefinition, Diagnostic> {
+        let start = self.current("a record definition")?.location.clone();
+        self.next_token();
+
+        let name = match &self.current("a record name")?.token {
+            Token::Word(name) => name.clone(),
+            _ => return Err(self.syntax_error("a record name")),
+        };
+        self.next_token();
+        self.expect(Symbol::OpeningBrace, "an opening brace")?;
+
+        let mut fields = Vec::new();
+
+        while !self.is(Symbol::ClosingBrace) {
+            let token = self.current("a field name or a closing brace")?;
+            let Token::Word(field) = &token.token else {
+                return Err(self.syntax_error("a field name or a closing brace"));
+            };
             self.next_token();
+            self.expect(Symbol::Colon, "a colon")?;
+            let r#type = self.parse_type_annotation()?;
 
-            if let Token::Symbol(Symbol::OpeningParenthesis) = self.current_token.unwrap().token {
-                self.next_token();
+            fields.push(FieldDefinition {
+                name: field.clone(),
+                r#type,
+                location: self.span(&token.location),
+            });
+
+            self.parse_separator()?;
+        }
+        self.next_token();
+
+        Ok(RecordDefinition {
+            name,
+            fields,
+            location: self.span(&start),
+        })
+    }
+
+    fn parse_function_definition(&mut self) -> Result<FunctionDefinition, Diagnostic> {
+        let start = self.current("a function definition")?.location.clone();
+        let prototype = self.parse_function_prototype()?;
+        let body = self.parse_function_body()?;
+
+        Ok(FunctionDefinition {
+            prototype,
+            body,
+            location: self.span(&start),
+        })
+    }
+
+    fn parse_function_prototype(&mut self) -> Result<FunctionPrototype, Diagnostic> {
+        let token = self.current("a function name")?;
 
-                let mut arguments = Vec::<VariableDefinition>::new();
+        if let Token::Word(name) = &token.token {
+            self.next_token();
+            self.expect(Symbol::OpeningParenthesis, "an opening parenthesis")?;
+
+            let mut arguments = Vec::<VariableDefinition>::new();
 
-                loop {
-                    match self.current_token.unwrap().token {
-                        Token::Symbol(Symbol::ClosingParenthesis) => {
+            loop {
+                match self.current("a closing parenthesis or a word")?.token {
+                    Token::Symbol(Symbol::ClosingParenthesis) => {
+                        self.next_token();
+                        break;
+                    }
+                    Token::Word(_) => {
+                        arguments.push(self.parse_variable_definition()?);
+                        if self.is(Symbol::Comma) {
                             self.next_token();
-                            break;
-                        }
-                        Token::Word(_) => {
-                            arguments.push(self.parse_variable_definition()?);
-                            if let Token::Symbol(Symbol::Comma) = self.current_token.unwrap().token
-                            {
-                                self.next_token();
-                            }
-                        }
-                        _ => {
-                            return Err(Box::new(SyntaxError {
-                                issue: "expected a closing parenthesis or a word".to_owned(),
-                                location: self.current_token.unwrap().location.clone(),
-                            }))
                         }
                     }
+                    _ => return Err(self.syntax_error("a closing parenthesis or a word")),
                 }
-
-                Ok(FunctionPrototype {
-                    name: name.clone(),
-                    arguments,
-                    location: self.current_token.unwrap().location.clone(),
-                })
-            } else {
-                Err(Box::new(SyntaxError {
-                    issue: "expected an opening parenthesis".to_owned(),
-                    location: self.current_token.unwrap().location.clone(),
-                }))
             }
+
+            let return_type = self.parse_optional_type_annotation()?;
+
+            Ok(FunctionPrototype {
+                name: name.clone(),
+                arguments,
+                return_type,
+                location: self.span(&token.location),
+            })
         } else {
-            Err(Box::new(SyntaxError {
-                issue: "expected a word".to_owned(),
-                location: self.current_token.unwrap().location.clone(),
-            }))
+            Err(self.syntax_error("a word"))
         }
     }
 
-    fn parse_variable_definition(&mut self) -> Result<VariableDefinition, Box<dyn Error>> {
-        if let Token::Word(name) = &self.current_token.unwrap().token {
+    fn parse_variable_definition(&mut self) -> Result<VariableDefinition, Diagnostic> {
+        let token = self.current("a word")?;
+
+        if let Token::Word(name) = &token.token {
             self.next_token();
             Ok(VariableDefinition {
                 name: name.to_owned(),
-                location: self.current_token.unwrap().location.clone(),
+                r#type: self.parse_optional_type_annotation()?,
+                location: token.location.clone(),
             })
         } else {
-            Err(Box::new(SyntaxError {
-                issue: "expected a word".to_owned(),
-                location: self.current_token.unwrap().location.clone(),
-            }))
+            Err(self.syntax_error("a word"))
         }
     }
 
-    fn parse_function_body(&mut self) -> Result<Expression, Box<dyn Error>> {
-        if let Token::Symbol(Symbol::OpeningBrace) = self.current_token.unwrap().token {
+    /// Parses a type annotation if a colon introduces one.
+    fn parse_optional_type_annotation(&mut self) -> Result<Option<TypeAnnotation>, Diagnostic> {
+        if self.is(Symbol::Colon) {
             self.next_token();
+            Ok(Some(self.parse_type_annotation()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn parse_type_annotation(&mut self) -> Result<TypeAnnotation, Diagnostic> {
+        let token = self.current("a type")?;
+
+        match &token.token {
+            Token::Word(name) => {
+                self.next_token();
+                let mut name = name.to_owned();
+
+                // Records of imported modules are qualified by the module name.
+                if self.is(Symbol::Dot) {
+                    self.next_token();
+                    let Token::Word(record) = &self.current("a type")?.token else {
+                        return Err(self.syntax_error("a type"));
+                    };
+                    self.next_token();
+                    name = format!("{}.{}", name, record);
+                }
 
-            if let Token::Symbol(Symbol::ClosingBrace) = self.current_token.unwrap().token {
+                Ok(TypeAnnotation::Named(NamedType {
+                    name,
+                    location: self.span(&token.location),
+                }))
+            }
+            Token::Symbol(Symbol::OpeningBracket) => {
                 self.next_token();
-                Ok(Expression::Empty)
+                let element = self.parse_type_annotation()?;
+                self.expect(Symbol::ClosingBracket, "a closing bracket")?;
+
+                Ok(TypeAnnotation::List(ListType {
+                    element: Box::new(element),
+                    location: self.span(&token.location),
+                }))
+            }
+            _ => Err(self.syntax_error("a type")),
+        }
+    }
+
+    fn parse_function_body(&mut self) -> Result<Expression, Diagnostic> {
+        self.parse_block()
+    }
+
+    /// Parses expressions surrounded by braces and separated by semicolons or
+    /// new lines. An invalid expression is reported and ends the block, the
+    /// parsing resuming after the closing brace.
+    fn parse_block(&mut self) -> Result<Expression, Diagnostic> {
+        let start = self.current("an opening brace")?.location.clone();
+        self.expect(Symbol::OpeningBrace, "an opening brace")?;
+        let depth = self.depth;
+
+        let mut expressions = Vec::new();
+
+        while !self.is(Symbol::ClosingBrace) {
+            let expression = if self.is_word("let") {
+                self.parse_let()
             } else {
-                let expression = self.parse_expression();
+                self.parse_expression()
+            };
+
+            match expression {
+                Ok(expression) => expressions.push(expression),
+                Err(diagnostic) => {
+                    self.report(diagnostic);
+                    self.synchronize(depth - 1);
+
+                    return Ok(Expression::Block(Block {
+                        expressions,
+                        location: self.span(&start),
+                    }));
+                }
+            }
 
-                if let Token::Symbol(Symbol::ClosingBrace) = self.current_token.unwrap().token {
+            if self.is(Symbol::Semicolon) {
+                while self.is(Symbol::Semicolon) {
                     self.next_token();
-                    expression
-                } else {
-                    Err(Box::new(SyntaxError {
-                        issue: "expected a closing brace".to_owned(),
-                        location: self.current_token.unwrap().location.clone(),
-                    }))
                 }
+            } else if !self.is(Symbol::ClosingBrace) && !self.is_on_new_line() {
+                self.report(match self.current_token {
+                    Some(_) => self.syntax_error("a semicolon, a new line or a closing brace"),
+                    None => self.syntax_error("a closing brace"),
+                });
+                self.synchronize(depth - 1);
+
+                return Ok(Expression::Block(Block {
+                    expressions,
+                    location: self.span(&start),
+                }));
             }
-        } else {
-            Err(Box::new(SyntaxError {
-                issue: "expected an opening brace".to_owned(),
-                location: self.current_token.unwrap().location.clone(),
-            }))
         }
+
+        self.next_token();
+
+        Ok(Expression::Block(Block {
+            expressions,
+            location: self.span(&start),
+        }))
     }
 
-    fn parse_expression(&mut self) -> Result<Expression, Box<dyn Error>> {
-        let expression_location = self.current_token.unwrap().location.clone();
-        if let Token::Word(word) = &self.current_token.unwrap().token {
-            if word == "if" {
-                let condition_location = self.current_token.unwrap().location.clone();
-                self.next_token();
+    fn parse_let(&mut self) -> Result<Expression, Diagnostic> {
+        let start = self.current("a let binding")?.location.clone();
+        self.next_token();
 
-                let condition = self.parse_expression()?;
+        let variable = self.parse_variable_definition()?;
+        self.expect(Symbol::Equal, "an equal sign")?;
+        let value = self.parse_expression()?;
 
-                if let Token::Symbol(Symbol::OpeningBrace) = self.current_token.unwrap().token {
+        Ok(Expression::Let(Let {
+            variable,
+            value: Box::new(value),
+            location: self.span(&start),
+        }))
+    }
+
+    fn parse_expression(&mut self) -> Result<Expression, Diagnostic> {
+        self.parse_binary(0)
+    }
+
+    /// Parses operations whose operators bind at least as tightly as the
+    /// given precedence.
+    fn parse_binary(&mut self, precedence: u8) -> Result<Expression, Diagnostic> {
+        let start = self.current("an expression")?.location.clone();
+        let mut left = self.parse_unary()?;
+
+        // An operator starting a line begins a new expression instead of
+        // continuing the previous one.
+        while let Some(operator) = self
+            .binary_operator()
+            .filter(|operator| operator.precedence() >= precedence && !self.is_on_new_line())
+        {
+            self.next_token();
+            let right = self.parse_binary(operator.precedence() + 1)?;
+
+            left = Expression::FunctionCall(FunctionCall {
+                name: operator.function().to_owned(),
+                parameters: vec![left, right],
+                operator: Some(operator),
+                location: self.span(&start),
+            });
+        }
+
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expression, Diagnostic> {
+        let token = self.current("an expression")?;
+
+        let operator = match token.token {
+            Token::Symbol(Symbol::Bang) => Operator::Not,
+            Token::Symbol(Symbol::Minus) => Operator::Negate,
+            _ => return self.parse_postfix(),
+        };
+        self.next_token();
+
+        // Negative numbers are literals rather than negations, so that the
+        // smallest integer can be written.
+        if operator == Operator::Negate {
+            if let Some(Token::Word(word)) = self.current_token.map(|token| &token.token) {
+                if is_integer(word) || is_float(word) {
                     self.next_token();
+                    let location = self.span(&token.location);
 
-                    let then = self.parse_expression()?;
+                    return Ok(self.parse_number(&format!("-{}", word), location));
+                }
+            }
+        }
 
-                    if let Token::Symbol(Symbol::ClosingBrace) = self.current_token.unwrap().token {
-                        self.next_token();
+        let operand = self.parse_unary()?;
 
-                        if let Token::Word(word) = &self.current_token.unwrap().token {
-                            if word == "else" {
-                                self.next_token();
-
-                                if let Token::Symbol(Symbol::OpeningBrace) =
-                                    self.current_token.unwrap().token
-                                {
-                                    self.next_token();
-
-                                    let r#else = self.parse_expression()?;
-
-                                    if let Token::Symbol(Symbol::ClosingBrace) =
-                                        self.current_token.unwrap().token
-                                    {
-                                        self.next_token();
-
-                                        Ok(Expression::Branch(Branch {
-                                            condition: Box::new(condition),
-                                            then: Box::new(then),
-                                            r#else: Box::new(r#else),
-                                            location: condition_location,
-                                        }))
-                                    } else {
-                                        Err(Box::new(SyntaxError {
-                                            issue: "expected a closing brace".to_owned(),
-                                            location: self.current_token.unwrap().location.clone(),
-                                        }))
-                                    }
-                                } else {
-                                    Err(Box::new(SyntaxError {
-                                        issue: "expected an opening brace".to_owned(),
-                                        location: self.current_token.unwrap().location.clone(),
-                                    }))
-                                }
-                            } else {
-                                Ok(Expression::Branch(Branch {
-                                    condition: Box::new(condition),
-                                    then: Box::new(then),
-                                    r#else: Box::new(Expression::Empty),
-                                    location: condition_location,
-                                }))
-                            }
-                        } else {
-                            Ok(Expression::Branch(Branch {
-                                condition: Box::new(condition),
-                                then: Box::new(then),
-                                r#else: Box::new(Expression::Empty),
-                                location: condition_location,
-                            }))
-                        }
-                    } else {
-                        Err(Box::new(SyntaxError {
-                            issue: "expected a closing brace".to_owned(),
-                            location: self.current_token.unwrap().location.clone(),
-                        }))
+        Ok(Expression::FunctionCall(FunctionCall {
+            name: operator.function().to_owned(),
+            parameters: vec![operand],
+            operator: Some(operator),
+            location: self.span(&token.location),
+        }))
+    }
+
+    /// The binary operator the current token stands for, if any.
+    fn binary_operator(&self) -> Option<Operator> {
+        match self.current_token?.token {
+            Token::Symbol(Symbol::DoublePipe) => Some(Operator::Or),
+            Token::Symbol(Symbol::DoubleAmpersand) => Some(Operator::And),
+            Token::Symbol(Symbol::DoubleEqual) => Some(Operator::Equal),
+            Token::Symbol(Symbol::BangEqual) => Some(Operator::NotEqual),
+            Token::Symbol(Symbol::Less) => Some(Operator::Less),
+            Token::Symbol(Symbol::LessEqual) => Some(Operator::LessEqual),
+            Token::Symbol(Symbol::Greater) => Some(Operator::Greater),
+            Token::Symbol(Symbol::GreaterEqual) => Some(Operator::GreaterEqual),
+            Token::Symbol(Symbol::Plus) => Some(Operator::Add),
+            Token::Symbol(Symbol::Minus) => Some(Operator::Sub),
+            Token::Symbol(Symbol::Star) => Some(Operator::Multiply),
+            Token::Symbol(Symbol::Slash) => Some(Operator::Divide),
+            Token::Symbol(Symbol::Percent) => Some(Operator::Modulo),
+            _ => None,
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Expression, Diagnostic> {
+        let token = self.current("an expression")?;
+        let expression_location = token.location.clone();
+
+        if self.is_word("let") {
+            Err(self.syntax_error("an expression, let bindings being only allowed in blocks"))
+        } else if self.is_word("if") {
+            self.parse_branch()
+        } else if self.is_word("match") {
+            self.parse_match()
+        } else if let Token::Word(word) = &token.token {
+            self.next_token();
+            // A parenthesis on the next line opens an expression of its own.
+            if self.is(Symbol::OpeningParenthesis) && !self.is_on_new_line() {
+                let parameters = self.parse_parameters()?;
+
+                Ok(Expression::FunctionCall(FunctionCall {
+                    name: word.clone(),
+                    parameters,
+                    operator: None,
+                    location: self.span(&expression_location),
+                }))
+            } else if word == "true" || word == "false" {
+                Ok(Expression::Literal(Literal::Bool(Bool {
+                    value: word == "true",
+                    location: expression_location,
+                })))
+            } else if is_integer(word) || is_float(word) {
+                Ok(self.parse_number(word, expression_location))
+            } else {
+                Ok(Expression::Variable(Variable {
+                    name: word.clone(),
+                    location: expression_location,
+                }))
+            }
+        } else if let Token::String(string) = &token.token {
+            self.next_token();
+            Ok(Expression::Literal(Literal::String(StringLiteral {
+                value: string.clone(),
+                location: expression_location,
+            })))
+        } else if let Token::Symbol(Symbol::Pipe | Symbol::DoublePipe) = &token.token {
+            self.parse_lambda()
+        } else if let Token::Symbol(Symbol::OpeningBracket) = &token.token {
+            self.parse_list()
+        } else if let Token::Symbol(Symbol::OpeningBrace) = &token.token {
+            self.parse_record()
+        } else if let Token::Symbol(Symbol::OpeningParenthesis) = &token.token {
+            self.next_token();
+            let expression = self.parse_expression()?;
+            self.expect(Symbol::ClosingParenthesis, "a closing parenthesis")?;
+
+            Ok(expression)
+        } else {
+            Err(self.syntax_error("an expression"))
+        }
+    }
+
+    /// Parses the parenthesized parameters of a call.
+    fn parse_parameters(&mut self) -> Result<Vec<Expression>, Diagnostic> {
+        let mut parameters = Vec::<Expression>::new();
+
+        self.expect(Symbol::OpeningParenthesis, "an opening parenthesis")?;
+
+        loop {
+            match self
+                .current("a closing parenthesis or an expression")?
+                .token
+            {
+                Token::Symbol(Symbol::ClosingParenthesis) => {
+                    self.next_token();
+                    break;
+                }
+                _ => {
+                    parameters.push(self.parse_expression()?);
+                    if self.is(Symbol::Comma) {
+                        self.next_token();
                     }
-                } else {
-                    Err(Box::new(SyntaxError {
-                        issue: "expected an opening brace".to_owned(),
-                        location: self.current_token.unwrap().location.clone(),
-                    }))
                 }
+            }
+        }
+
+        Ok(parameters)
+    }
+
+    /// Builds the literal of a word which is an integer or a float. Literals
+    /// out of range are reported, but parsing goes on as they still are
+    /// numbers.
+    fn parse_number(&mut self, word: &str, location: Location) -> Expression {
+        if is_integer(word) {
+            let value = word.parse::<i64>().unwrap_or_else(|_| {
+                self.report(
+                    Diagnostic::error(
+                        Code::NumberOutOfRange,
+                        format!("integer literal {} is out of range", word),
+                    )
+                    .at(location.clone())
+                    .with_help(format!(
+                        "integers range from {} to {}",
+                        i64::MIN,
+                        i64::MAX
+                    )),
+                );
+
+                0
+            });
+
+            Expression::Literal(Literal::Number(Number { value, location }))
+        } else {
+            let value = word.parse::<f64>().unwrap();
+            if value.is_infinite() {
+                self.report(
+                    Diagnostic::error(
+                        Code::NumberOutOfRange,
+                        format!("float literal {} is out of range", word),
+                    )
+                    .at(location.clone())
+                    .with_help(format!("floats range up to {:e}", f64::MAX)),
+                );
+            }
+
+            Expression::Literal(Literal::Float(Float {
+                value,
+                raw: word.to_owned(),
+                location,
+            }))
+        }
+    }
+
+    /// Parses an `if` expression, along with the `else if` expressions
+    /// chained to it.
+    fn parse_branch(&mut self) -> Result<Expression, Diagnostic> {
+        let start = self.current("an if expression")?.location.clone();
+        self.next_token();
+
+        let condition = self.parse_expression()?;
+        let then = self.parse_block()?;
+
+        let r#else = if self.is_word("else") {
+            self.next_token();
+
+            if self.is_word("if") {
+                self.parse_branch()?
             } else {
-                self.next_token();
-                if let Token::Symbol(Symbol::OpeningParenthesis) = self.current_token.unwrap().token
-                {
-                    let mut parameters = Vec::<Expression>::new();
+                self.parse_block()?
+            }
+        } else {
+            Expression::Empty
+        };
 
-                    self.next_token();
+        Ok(Expression::Branch(Branch {
+            condition: Box::new(condition),
+            then: Box::new(then),
+            r#else: Box::new(r#else),
+            location: self.span(&start),
+        }))
+    }
+
+    fn parse_match(&mut self) -> Result<Expression, Diagnostic> {
+        let start = self.current("a match expression")?.location.clone();
+        self.next_token();
+
+        let value = self.parse_expression()?;
+        self.expect(Symbol::OpeningBrace, "an opening brace")?;
+
+        let mut arms = Vec::new();
+
+        while !self.is(Symbol::ClosingBrace) {
+            let arm = self.parse_arm()?;
+            // The closing brace of a block already ends its arm.
+            let delimited = matches!(arm.body, Expression::Block(_));
+            arms.push(arm);
+
+            if !delimited || self.is(Symbol::Comma) {
+                self.parse_separator()?;
+            }
+        }
+        self.next_token();
+
+        Ok(Expression::Match(Match {
+            value: Box::new(value),
+            arms,
+            location: self.span(&start),
+        }))
+    }
+
+    fn parse_arm(&mut self) -> Result<Arm, Diagnostic> {
+        let token = self.current("a pattern")?;
+
+        let pattern = if self.is_word("_") {
+            self.next_token();
+            Pattern::Wildcard(token.location.clone())
+        } else {
+            match self.parse_unary()? {
+                Expression::Literal(literal) => Pattern::Literal(literal),
+                expression => {
+                    return Err(Diagnostic::error(
+                        Code::UnexpectedToken,
+                        "expected a literal or `_` pattern".to_owned(),
+                    )
+                    .at(expression.location().unwrap_or(&token.location).clone()))
+                }
+            }
+        };
+
+        self.expect(Symbol::FatArrow, "an arrow")?;
+
+        let body = if self.is(Symbol::OpeningBrace) {
+            self.parse_block()?
+        } else {
+            self.parse_expression()?
+        };
+
+        Ok(Arm {
+            pattern,
+            body,
+            location: self.span(&token.location),
+        })
+    }
+
+    /// Parses a lambda such as `|a, b| a + b`, whose arguments are between
+    /// pipes, `||` introducing one without arguments.
+    fn parse_lambda(&mut self) -> Result<Expression, Diagnostic> {
+        let start = self.current("a lambda")?.location.clone();
+        let mut arguments = Vec::<VariableDefinition>::new();
+
+        if self.is(Symbol::DoublePipe) {
+            self.next_token();
+        } else {
+            self.expect(Symbol::Pipe, "a pipe")?;
 
-                    loop {
-                        match self.current_token.unwrap().token {
-                            Token::Symbol(Symbol::ClosingParenthesis) => {
-                                self.next_token();
-                                break;
-                            }
-                            _ => {
-                                parameters.push(self.parse_expression()?);
-                                if let Token::Symbol(Symbol::Comma) =
-                                    self.current_token.unwrap().token
-                                {
-                                    self.next_token();
-                                }
-                            }
+            loop {
+                match self.current("a pipe or a word")?.token {
+                    Token::Symbol(Symbol::Pipe) => {
+                        self.next_token();
+                        break;
+                    }
+                    Token::Word(_) => {
+                        arguments.push(self.parse_variable_definition()?);
+                        if self.is(Symbol::Comma) {
+                            self.next_token();
                         }
                     }
+                    _ => return Err(self.syntax_error("a pipe or a word")),
+                }
+            }
+        }
+
+        let body = if self.is(Symbol::OpeningBrace) {
+            self.parse_block()?
+        } else {
+            self.parse_expression()?
+        };
+
+        Ok(Expression::Lambda(Lambda {
+            arguments,
+            body: Box::new(body),
+            location: self.span(&start),
+        }))
+    }
+
+    /// Consumes what separates the items of a braced list, such as the arms of
+    /// a match: a comma, or nothing when the next item starts on a new line or
+    /// the list ends.
+    fn parse_separator(&mut self) -> Result<(), Diagnostic> {
+        if self.is(Symbol::Comma) {
+            self.next_token();
+            Ok(())
+        } else if self.is(Symbol::ClosingBrace) || self.is_on_new_line() {
+            Ok(())
+        } else {
+            Err(match self.current_token {
+                Some(_) => self.syntax_error("a comma, a new line or a closing brace"),
+                None => self.syntax_error("a closing brace"),
+            })
+        }
+    }
+
+    fn parse_record(&mut self) -> Result<Expression, Diagnostic> {
+        let start = self.current("an opening brace")?.location.clone();
+        self.expect(Symbol::OpeningBrace, "an opening brace")?;
+
+        let mut fields = Vec::new();
 
-                    Ok(Expression::FunctionCall(FunctionCall {
-                        name: word.clone(),
-                        parameters,
-                        location: expression_location,
-                    }))
-                } else if let Ok(number) = word.parse::<u32>() {
-                    Ok(Expression::Literal(Literal::Number(Number {
-                        value: number,
-                        location: self.current_token.unwrap().location.clone(),
-                    })))
-                } else {
-                    Ok(Expression::Variable(Variable {
-                        name: word.clone(),
-                        location: self.current_token.unwrap().location.clone(),
-                    }))
+        while !self.is(Symbol::ClosingBrace) {
+            let token = self.current("a field name or a closing brace")?;
+            let Token::Word(name) = &token.token else {
+                return Err(self.syntax_error("a field name or a closing brace"));
+            };
+            self.next_token();
+            self.expect(Symbol::Colon, "a colon")?;
+            let value = self.parse_expression()?;
+
+            fields.push(FieldValue {
+                name: name.clone(),
+                value,
+                location: self.span(&token.location),
+            });
+
+            self.parse_separator()?;
+        }
+        self.next_token();
+
+        Ok(Expression::Record(Record {
+            fields,
+            location: self.span(&start),
+        }))
+    }
+
+    /// Parses an expression followed by the fields accessed on it, if any.
+    /// A name followed by another one and parameters, as in `math.square(2)`,
+    /// calls a function of an imported module instead.
+    fn parse_postfix(&mut self) -> Result<Expression, Diagnostic> {
+        let start = self.current("an expression")?.location.clone();
+        let mut expression = self.parse_primary()?;
+
+        while self.is(Symbol::Dot) && !self.is_on_new_line() {
+            self.next_token();
+
+            let Token::Word(field) = &self.current("a field name")?.token else {
+                return Err(self.syntax_error("a field name"));
+            };
+            self.next_token();
+
+            expression = match expression {
+                Expression::Variable(module)
+                    if self.is(Symbol::OpeningParenthesis) && !self.is_on_new_line() =>
+                {
+                    Expression::FunctionCall(FunctionCall {
+                        name: format!("{}.{}", module.name, field),
+                        parameters: self.parse_parameters()?,
+                        operator: None,
+                        location: self.span(&start),
+                    })
+                }
+                expression => Expression::FieldAccess(FieldAccess {
+                    record: Box::new(expression),
+                    field: field.clone(),
+                    location: self.span(&start),
+                }),
+            };
+        }
+
+        Ok(expression)
+    }
+
+    fn parse_list(&mut self) -> Result<Expression, Diagnostic> {
+        let start = self.current("an opening bracket")?.location.clone();
+        self.expect(Symbol::OpeningBracket, "an opening bracket")?;
+
+        let mut elements = Vec::new();
+
+        loop {
+            match self.current("a closing bracket or an expression")?.token {
+                Token::Symbol(Symbol::ClosingBracket) => {
+                    self.next_token();
+                    break;
+                }
+                _ => {
+                    elements.push(self.parse_expression()?);
+                    if self.is(Symbol::Comma) {
+                        self.next_token();
+                    }
                 }
             }
+        }
+
+        Ok(Expression::List(List {
+            elements,
+            location: self.span(&start),
+        }))
+    }
+
+    /// Returns the current token, or reports the end of file when something
+    /// was still expected.
+    fn current(&self, expected: &str) -> Result<&'a LocatedToken, Diagnostic> {
+        self.current_token
+            .ok_or_else(|| self.syntax_error(expected))
+    }
+
+    /// Tells whether the current token is the given symbol.
+    fn is(&self, symbol: Symbol) -> bool {
+        matches!(self.current_token, Some(LocatedToken { token: Token::Symbol(s), .. }) if *s == symbol)
+    }
+
+    /// Tells whether the current token is the given word.
+    fn is_word(&self, word: &str) -> bool {
+        matches!(self.current_token, Some(LocatedToken { token: Token::Word(w), .. }) if w == word)
+    }
+
+    /// Tells whether the current token starts a line after the previous one.
+    fn is_on_new_line(&self) -> bool {
+        match (self.previous_token, self.current_token) {
+            (Some(previous), Some(current)) => {
+                current.location.start.line > previous.location.end.line
+            }
+            _ => false,
+        }
+    }
+
+    /// Consumes the current token if it is the given symbol.
+    fn expect(&mut self, symbol: Symbol, expected: &str) -> Result<(), Diagnostic> {
+        if self.is(symbol) {
+            self.next_token();
+            Ok(())
         } else {
-            Err(Box::new(SyntaxError {
-                issue: "expected a word".to_owned(),
-                location: self.current_token.unwrap().location.clone(),
-            }))
+            Err(self.syntax_error(expected))
+        }
+    }
+
+    /// Returns the span going from a location to the end of the last consumed
+    /// token.
+    fn span(&self, start: &Location) -> Location {
+        match self.previous_token {
+            Some(token) => start.to(&token.location),
+            None => start.clone(),
+        }
+    }
+
+    fn syntax_error(&self, expected: &str) -> Diagnostic {
+        match self.current_token {
+            Some(token) => {
+                Diagnostic::error(Code::UnexpectedToken, format!("expected {}", expected))
+                    .at(token.location.clone())
+            }
+            None => {
+                let diagnostic = Diagnostic::error(
+                    Code::UnexpectedEndOfFile,
+                    format!("unexpected end of file, expected {}", expected),
+                );
+
+                match self.previous_token {
+                    Some(token) => diagnostic.at(token.location.clone()),
+                    None => diagnostic,
+                }
+            }
+        }
+    }
+
+    fn report(&mut self, diagnostic: Diagnostic) {
+        // Every enclosing construct would complain about the end of file
+        // once recovered from, a single report is enough.
+        let reached_end_of_file = self
+            .diagnostics
+            .last()
+            .is_some_and(|last| last.code == Code::UnexpectedEndOfFile);
+
+        if !(reached_end_of_file && self.current_token.is_none()) {
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    /// Skips tokens until the brace bringing the nesting back to `depth` is
+    /// closed.
+    fn synchronize(&mut self, depth: usize) {
+        while let Some(token) = self.current_token {
+            self.next_token();
+
+            if let Token::Symbol(Symbol::ClosingBrace) = token.token {
+                if self.depth <= depth {
+                    break;
+                }
+            }
         }
     }
 
     fn next_token(&mut self) {
+        if let Some(token) = self.current_token {
+            match token.token {
+                Token::Symbol(Symbol::OpeningBrace) => self.depth += 1,
+                Token::Symbol(Symbol::ClosingBrace) => self.depth = self.depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+
+        self.previous_token = self.current_token;
         self.current_token = self.tokens.next();
     }
 }
+
+/// Tells whether a word is an integer literal, possibly negative.
+fn is_integer(word: &str) -> bool {
+    let digits = word.strip_prefix('-').unwrap_or(word);
+    !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Tells whether a word is a float literal such as `1.5` or `-2e10`.
+fn is_float(word: &str) -> bool {
+    let digits = word.strip_prefix('-').unwrap_or(word);
+    digits.starts_with(|c: char| c.is_ascii_digit())
+        && digits.contains(['.', 'e', 'E'])
+        && digits.parse::<f64>().is_ok()
+}
@@ -1,27 +1,41 @@
-use std::error::Error;
+use std::collections::HashMap;
 
-#[derive(Debug)]
-pub struct FileExtensionError {
-    path: String,
-}
+use super::diagnostics::{Code, Diagnostic};
 
-impl std::fmt::Display for FileExtensionError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}'s file extension is not `.id`", self.path)
+pub fn read(path: &str) -> Result<String, Diagnostic> {
+    if path.ends_with(".id") {
+        std::fs::read_to_string(path)
+            .map_err(|err| Diagnostic::error(Code::Io, format!("could not read {}: {}", path, err)))
+    } else {
+        Err(Diagnostic::error(
+            Code::FileExtension,
+            format!("{}'s file extension is not `.id`", path),
+        ))
     }
 }
 
-impl Error for FileExtensionError {}
+/// Keeps the content of every file read so far, so that diagnostics can
+/// later quote the source they point at.
+#[derive(Debug, Default)]
+pub struct Sources {
+    files: HashMap<String, String>,
+}
 
-pub fn read(path: &str) -> Result<String, Box<dyn Error>> {
-    if path.ends_with(".id") {
-        match std::fs::read_to_string(&path) {
-            Ok(content) => Ok(content),
-            Err(err) => Err(Box::new(err)),
+impl Sources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn read(&mut self, path: &str) -> Result<&str, Diagnostic> {
+        if !self.files.contains_key(path) {
+            let content = read(path)?;
+            self.files.insert(path.to_owned(), content);
         }
-    } else {
-        Err(Box::new(FileExtensionError {
-            path: path.to_owned(),
-        }))
+
+        Ok(&self.files[path])
+    }
+
+    pub fn get(&self, path: &str) -> Option<&str> {
+        self.files.get(path).map(|content| content.as_str())
     }
 }
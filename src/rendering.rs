@@ -0,0 +1,160 @@
+use super::diagnostics::{Diagnostic, Severity};
+use super::lexing::Location;
+use super::reading::Sources;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const CYAN: &str = "\x1b[1;36m";
+const BLUE: &str = "\x1b[1;34m";
+
+/// Renders diagnostics for humans, quoting the source lines they point at and
+/// underlining the offending spans.
+pub struct Renderer<'a> {
+    sources: &'a Sources,
+    colored: bool,
+}
+
+impl<'a> Renderer<'a> {
+    pub fn new(sources: &'a Sources, colored: bool) -> Self {
+        Self { sources, colored }
+    }
+
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        let severity_color = match diagnostic.severity {
+            Severity::Error => RED,
+            Severity::Warning => YELLOW,
+            Severity::Note => CYAN,
+        };
+
+        let mut output = format!(
+            "{}{}",
+            self.paint(
+                &format!("{}[{}]", diagnostic.severity, diagnostic.code),
+                severity_color
+            ),
+            self.paint(&format!(": {}", diagnostic.message), BOLD),
+        );
+
+        let gutter = diagnostic
+            .location
+            .as_deref()
+            .into_iter()
+            .chain(diagnostic.labels.iter().map(|label| &label.location))
+            .map(|location| location.end.line.to_string().len())
+            .max()
+            .unwrap_or(0);
+
+        if let Some(location) = &diagnostic.location {
+            output.push_str(&format!(
+                "\n{}{} {}",
+                " ".repeat(gutter),
+                self.paint("-->", BLUE),
+                location
+            ));
+            output.push_str(&self.snippet(location, gutter, '^', severity_color, ""));
+        }
+
+        for label in &diagnostic.labels {
+            if diagnostic
+                .location
+                .as_ref()
+                .is_none_or(|location| location.path != label.location.path)
+            {
+                output.push_str(&format!(
+                    "\n{}{} {}",
+                    " ".repeat(gutter),
+                    self.paint(":::", BLUE),
+                    label.location
+                ));
+            }
+            output.push_str(&self.snippet(&label.location, gutter, '-', BLUE, &label.message));
+        }
+
+        if let Some(help) = &diagnostic.help {
+            output.push_str(&format!(
+                "\n{} {} {}",
+                " ".repeat(gutter),
+                self.paint("= help:", BOLD),
+                help
+            ));
+        }
+
+        output
+    }
+
+    /// Quotes the lines a location spans and underlines it. Only the first
+    /// and last lines of long spans are quoted.
+    fn snippet(
+        &self,
+        location: &Location,
+        gutter: usize,
+        marker: char,
+        color: &str,
+        message: &str,
+    ) -> String {
+        let source = match self.sources.get(&location.path) {
+            Some(source) => source,
+            None => return String::new(),
+        };
+
+        let bar = self.paint("|", BLUE);
+        let mut snippet = format!("\n{} {}", " ".repeat(gutter), bar);
+
+        for number in location.start.line..=location.end.line {
+            if number > location.start.line + 1 && number < location.end.line {
+                if number == location.start.line + 2 {
+                    snippet.push_str(&format!("\n{}", self.paint("...", BLUE)));
+                }
+                continue;
+            }
+
+            // The lexer counts a tabulation as four columns.
+            let line = match source.lines().nth(number - 1) {
+                Some(line) => line.replace('\t', "    "),
+                None => break,
+            };
+
+            let start = if number == location.start.line {
+                location.start.column
+            } else {
+                line.len() - line.trim_start().len() + 1
+            };
+            let end = if number == location.end.line {
+                location.end.column
+            } else {
+                line.trim_end().chars().count() + 1
+            };
+
+            let mut underline = format!(
+                "{}{}",
+                " ".repeat(start.saturating_sub(1)),
+                marker.to_string().repeat(end.saturating_sub(start).max(1))
+            );
+            if number == location.end.line && !message.is_empty() {
+                underline.push_str(&format!(" {}", message));
+            }
+
+            snippet.push_str(&format!(
+                "\n{} {} {}\n{} {} {}",
+                self.paint(&format!("{:>width$}", number, width = gutter), BLUE),
+                bar,
+                line.trim_end(),
+                " ".repeat(gutter),
+                bar,
+                self.paint(&underline, color),
+            ));
+        }
+
+        snippet
+    }
+
+    fn paint(&self, text: &str, color: &str) -> String {
+        if self.colored {
+            format!("{}{}{}", color, text, RESET)
+        } else {
+            text.to_owned()
+        }
+    }
+}
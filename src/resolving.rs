@@ -0,0 +1,395 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::ast::{Callee, Expression, Import, Statement, TypeAnnotation, Variable, AST};
+use super::diagnostics::{Code, Diagnostic};
+use super::lexing::{Lexer, Location};
+use super::parsing::Parser;
+use super::reading::Sources;
+
+/// Loads a program along with every module it imports, directly or not, and
+/// links them into a single AST, so that later stages see the whole module
+/// graph at once. What an imported module defines is named after it,
+/// `square` of `math.id` becoming `math.square`, while what the program
+/// defines keeps its name.
+///
+/// Like parsing, resolution does not stop at the first error: the returned
+/// AST holds every module which could be loaded.
+pub fn resolve(sources: &mut Sources, path: &str) -> (AST, Vec<Diagnostic>) {
+    let mut resolver = Resolver {
+        sources,
+        modules: HashMap::new(),
+        loading: Vec::new(),
+        statements: Vec::new(),
+        diagnostics: Vec::new(),
+    };
+
+    if let Ok(canonical) = Path::new(path).canonicalize() {
+        resolver.loading.push((canonical, path.to_owned()));
+    }
+
+    match resolver.parse(path) {
+        Ok(ast) => resolver.load(ast, path, None),
+        Err(mut diagnostics) => resolver.diagnostics.append(&mut diagnostics),
+    }
+
+    (
+        AST {
+            statements: resolver.statements,
+        },
+        resolver.diagnostics,
+    )
+}
+
+struct Resolver<'a> {
+    sources: &'a mut Sources,
+    /// The name of every module loaded so far, by canonical path, so that a
+    /// module imported several times is only loaded once.
+    modules: HashMap<PathBuf, String>,
+    /// The modules being loaded, each one importing the next, along with
+    /// their path as written.
+    loading: Vec<(PathBuf, String)>,
+    statements: Vec<Statement>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Resolver<'_> {
+    fn parse(&mut self, path: &str) -> Result<AST, Vec<Diagnostic>> {
+        let source = self.sources.read(path).map_err(|err| vec![err])?;
+        let (tokens, mut diagnostics) = Lexer::new(path).tokenize(source.chars());
+        self.diagnostics.append(&mut diagnostics);
+        let mut tokens_stream = tokens.iter();
+
+        let (ast, mut diagnostics) = Parser::new(&mut tokens_stream).parse();
+        self.diagnostics.append(&mut diagnostics);
+
+        Ok(ast)
+    }
+
+    /// Loads the modules a module imports, then adds its own statements.
+    fn load(&mut self, ast: AST, path: &str, module: Option<&str>) {
+        let mut imports = HashSet::new();
+        let mut statements = Vec::new();
+
+        for statement in ast.statements {
+            match statement {
+                Statement::Import(import) => {
+                    if let Some(name) = self.import(path, &import) {
+                        imports.insert(name);
+                    }
+                }
+                statement => statements.push(statement),
+            }
+        }
+
+        let mut linker = Linker {
+            module,
+            functions: HashSet::new(),
+            constants: HashSet::new(),
+            records: HashSet::new(),
+            imports: &imports,
+            diagnostics: Vec::new(),
+        };
+
+        for statement in &statements {
+            match statement {
+                Statement::FunctionDefinition(function) => {
+                    linker.functions.insert(function.prototype.name.clone());
+                }
+                Statement::RecordDefinition(record) => {
+                    linker.records.insert(record.name.clone());
+                }
+                Statement::ConstantDefinition(constant) => {
+                    linker.constants.insert(constant.variable.name.clone());
+                }
+                Statement::Import(_) => {}
+            }
+        }
+
+        for statement in &mut statements {
+            linker.link_statement(statement);
+        }
+
+        self.diagnostics.append(&mut linker.diagnostics);
+        self.statements.append(&mut statements);
+    }
+
+    /// Loads an imported module unless it already was, returning its name.
+    fn import(&mut self, importer: &str, import: &Import) -> Option<String> {
+        let path = Path::new(importer)
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join(&import.path);
+        let displayed = path.to_string_lossy().into_owned();
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let canonical = match path.canonicalize() {
+            Ok(canonical) => canonical,
+            Err(err) => {
+                self.diagnostics.push(
+                    Diagnostic::error(Code::Io, format!("could not read {}: {}", displayed, err))
+                        .at(import.location.clone()),
+                );
+                return None;
+            }
+        };
+
+        if let Some(start) = self
+            .loading
+            .iter()
+            .position(|(loading, _)| *loading == canonical)
+        {
+            let cycle = self.loading[start..]
+                .iter()
+                .map(|(_, path)| path.as_str())
+                .chain([displayed.as_str()])
+                .collect::<Vec<&str>>()
+                .join(" -> ");
+
+            self.diagnostics.push(
+                Diagnostic::error(
+                    Code::ImportCycle,
+                    format!("modules import each other: {}", cycle),
+                )
+                .at(import.location.clone())
+                .with_help("move what the modules share to another one".to_owned()),
+            );
+            return None;
+        }
+
+        if let Some(name) = self.modules.get(&canonical) {
+            return Some(name.clone());
+        }
+
+        if let Some((other, _)) = self.modules.iter().find(|(_, other)| **other == name) {
+            self.diagnostics.push(
+                Diagnostic::error(
+                    Code::ConflictingModules,
+                    format!(
+                        "module \"{}\" is also the name of {}",
+                        name,
+                        other.to_string_lossy()
+                    ),
+                )
+                .at(import.location.clone())
+                .with_help("modules are named after their file, rename one of them".to_owned()),
+            );
+            return None;
+        }
+
+        self.modules.insert(canonical.clone(), name.clone());
+
+        let ast = match self.parse(&displayed) {
+            Ok(ast) => ast,
+            Err(diagnostics) => {
+                // Errors reading the module itself point at its import.
+                for diagnostic in diagnostics {
+                    self.diagnostics.push(match diagnostic.location {
+                        Some(_) => diagnostic,
+                        None => diagnostic.at(import.location.clone()),
+                    });
+                }
+                return None;
+            }
+        };
+
+        self.loading.push((canonical, displayed.clone()));
+        self.load(ast, &displayed, Some(&name));
+        self.loading.pop();
+
+        Some(name)
+    }
+}
+
+/// Names the functions, constants and records of a module after it, and checks the
+/// qualified names it uses refer to modules it imports.
+struct Linker<'a> {
+    /// The name of the module, the program itself having none.
+    module: Option<&'a str>,
+    functions: HashSet<String>,
+    constants: HashSet<String>,
+    records: HashSet<String>,
+    imports: &'a HashSet<String>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Linker<'_> {
+    fn qualify(&self, name: &str) -> String {
+        match self.module {
+            Some(module) => format!("{}.{}", module, name),
+            None => name.to_owned(),
+        }
+    }
+
+    fn link_statement(&mut self, statement: &mut Statement) {
+        match statement {
+            Statement::FunctionDefinition(function) => {
+                function.prototype.name = self.qualify(&function.prototype.name);
+
+                let mut bound = Vec::new();
+                for argument in &mut function.prototype.arguments {
+                    if let Some(annotation) = &mut argument.r#type {
+                        self.link_annotation(annotation);
+                    }
+                    bound.push(argument.name.clone());
+                }
+                if let Some(annotation) = &mut function.prototype.return_type {
+                    self.link_annotation(annotation);
+                }
+
+                self.link_expression(&mut function.body, &mut bound);
+            }
+            Statement::RecordDefinition(record) => {
+                record.name = self.qualify(&record.name);
+
+                for field in &mut record.fields {
+                    self.link_annotation(&mut field.r#type);
+                }
+            }
+            Statement::ConstantDefinition(constant) => {
+                constant.variable.name = self.qualify(&constant.variable.name);
+
+                if let Some(annotation) = &mut constant.variable.r#type {
+                    self.link_annotation(annotation);
+                }
+
+                self.link_expression(&mut constant.value, &mut Vec::new());
+            }
+            Statement::Import(_) => {}
+        }
+    }
+
+    fn link_annotation(&mut self, annotation: &mut TypeAnnotation) {
+        match annotation {
+            TypeAnnotation::Named(named) => match named.name.split_once('.') {
+                Some((module, _)) => self.check_module(module, &named.location),
+                None if self.records.contains(&named.name) => {
+                    named.name = self.qualify(&named.name)
+                }
+                None => {}
+            },
+            TypeAnnotation::List(list) => self.link_annotation(&mut list.element),
+        }
+    }
+
+    /// Tells whether a function or a constant of the module has the given
+    /// name, both of which get qualified alike.
+    fn global(&self, name: &str) -> Option<()> {
+        (self.functions.contains(name) || self.constants.contains(name)).then_some(())
+    }
+
+    /// Links an expression in which the given variables are bound, and
+    /// therefore take precedence over functions of the same name.
+    fn link_expression(&mut self, expression: &mut Expression, bound: &mut Vec<String>) {
+        match expression {
+            Expression::FunctionCall(call) => {
+                for parameter in &mut call.parameters {
+                    self.link_expression(parameter, bound);
+                }
+
+                match call.name.split_once('.') {
+                    Some((module, _)) => self.check_module(module, &call.location),
+                    None => {
+                        if let Callee::Function(()) = call.callee(
+                            || bound.contains(&call.name).then_some(()),
+                            || self.global(&call.name),
+                        ) {
+                            call.name = self.qualify(&call.name);
+                        }
+                    }
+                }
+            }
+            Expression::Variable(variable) => {
+                if let Callee::Function(()) = Callee::lookup(
+                    || bound.contains(&variable.name).then_some(()),
+                    || self.global(&variable.name),
+                ) {
+                    variable.name = self.qualify(&variable.name);
+                }
+            }
+            Expression::FieldAccess(access) => {
+                // A function or a constant of an imported module.
+                if let Expression::Variable(module) = &*access.record {
+                    if !bound.contains(&module.name) && self.imports.contains(&module.name) {
+                        *expression = Expression::Variable(Variable {
+                            name: format!("{}.{}", module.name, access.field),
+                            location: access.location.clone(),
+                        });
+                        return;
+                    }
+                }
+
+                self.link_expression(&mut access.record, bound);
+            }
+            Expression::Block(block) => {
+                let outer = bound.len();
+
+                for expression in &mut block.expressions {
+                    self.link_expression(expression, bound);
+                }
+
+                bound.truncate(outer);
+            }
+            Expression::Let(binding) => {
+                if let Some(annotation) = &mut binding.variable.r#type {
+                    self.link_annotation(annotation);
+                }
+                self.link_expression(&mut binding.value, bound);
+
+                // Bound for the rest of the enclosing block.
+                bound.push(binding.variable.name.clone());
+            }
+            Expression::Lambda(lambda) => {
+                let outer = bound.len();
+
+                for argument in &mut lambda.arguments {
+                    if let Some(annotation) = &mut argument.r#type {
+                        self.link_annotation(annotation);
+                    }
+                    bound.push(argument.name.clone());
+                }
+                self.link_expression(&mut lambda.body, bound);
+
+                bound.truncate(outer);
+            }
+            Expression::Branch(branch) => {
+                self.link_expression(&mut branch.condition, bound);
+                self.link_expression(&mut branch.then, bound);
+                self.link_expression(&mut branch.r#else, bound);
+            }
+            Expression::Match(r#match) => {
+                self.link_expression(&mut r#match.value, bound);
+                for arm in &mut r#match.arms {
+                    self.link_expression(&mut arm.body, bound);
+                }
+            }
+            Expression::List(list) => {
+                for element in &mut list.elements {
+                    self.link_expression(element, bound);
+                }
+            }
+            Expression::Record(record) => {
+                for field in &mut record.fields {
+                    self.link_expression(&mut field.value, bound);
+                }
+            }
+            Expression::Literal(_) | Expression::Empty => {}
+        }
+    }
+
+    fn check_module(&mut self, module: &str, location: &Location) {
+        if !self.imports.contains(module) {
+            self.diagnostics.push(
+                Diagnostic::error(
+                    Code::UnknownModule,
+                    format!("module \"{}\" is not imported", module),
+                )
+                .at(location.clone())
+                .with_help(format!("import it with `import \"{}.id\"`", module)),
+            );
+        }
+    }
+}
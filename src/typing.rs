@@ -0,0 +1,1348 @@
+use std::collections::{HashMap, HashSet};
+
+use super::ast::{
+    Callee, Expression, FunctionCall, FunctionDefinition, Lambda, Literal, Pattern, Record,
+    Statement, TypeAnnotation, AST,
+};
+use super::diagnostics::{Code, Diagnostic};
+use super::lexing::Location;
+use super::native::native_functions_map;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Type {
+    Int,
+    Float,
+    Bool,
+    Unit,
+    String,
+    List(Box<Type>),
+    /// A declared record, named after its declaration.
+    Record(String),
+    Function(Vec<Type>, Box<Type>),
+    /// A type which is yet to be inferred.
+    Variable(usize),
+    /// A type parameter of a native function signature, standing for a
+    /// possibly different type at each call.
+    Parameter(usize),
+}
+
+impl Type {
+    pub fn list(element: Type) -> Type {
+        Type::List(Box::new(element))
+    }
+
+    pub fn function(parameters: Vec<Type>, result: Type) -> Type {
+        Type::Function(parameters, Box::new(result))
+    }
+
+    /// The type a name refers to in an annotation, if any.
+    pub fn named(name: &str) -> Option<Type> {
+        match name {
+            "Int" => Some(Type::Int),
+            "Float" => Some(Type::Float),
+            "Bool" => Some(Type::Bool),
+            "Unit" => Some(Type::Unit),
+            "String" => Some(Type::String),
+            _ => None,
+        }
+    }
+
+    /// The type an annotation refers to, if every name in it is either
+    /// built in or a record.
+    pub fn annotated(
+        annotation: &TypeAnnotation,
+        is_record: &dyn Fn(&str) -> bool,
+    ) -> Option<Type> {
+        match annotation {
+            TypeAnnotation::Named(named) => Type::named(&named.name)
+                .or_else(|| is_record(&named.name).then(|| Type::Record(named.name.clone()))),
+            TypeAnnotation::List(list) => Type::annotated(&list.element, is_record).map(Type::list),
+        }
+    }
+}
+
+impl std::fmt::Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Type::Int => write!(f, "Int"),
+            Type::Float => write!(f, "Float"),
+            Type::Bool => write!(f, "Bool"),
+            Type::Unit => write!(f, "Unit"),
+            Type::String => write!(f, "String"),
+            Type::List(element) => write!(f, "[{}]", element),
+            Type::Record(name) => write!(f, "{}", name),
+            Type::Function(parameters, result) => write!(
+                f,
+                "({}) -> {}",
+                parameters
+                    .iter()
+                    .map(|parameter| parameter.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", "),
+                result
+            ),
+            Type::Variable(_) => write!(f, "_"),
+            Type::Parameter(parameter) => write!(f, "{}", (b'a' + *parameter as u8) as char),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Signature {
+    pub parameters: Vec<Type>,
+    pub result: Type,
+}
+
+impl Signature {
+    pub fn new(parameters: Vec<Type>, result: Type) -> Self {
+        Self { parameters, result }
+    }
+}
+
+impl std::fmt::Display for Signature {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "({}) -> {}",
+            self.parameters
+                .iter()
+                .map(|parameter| parameter.to_string())
+                .collect::<Vec<String>>()
+                .join(", "),
+            self.result
+        )
+    }
+}
+
+/// A call to an overloaded function, whose overload is selected once the
+/// types of its parameters tell which one it is.
+#[derive(Debug, Clone)]
+struct Constraint {
+    overloads: Vec<Signature>,
+    parameters: Vec<Type>,
+    result: Type,
+    /// What is called, as reported when no overload accepts the parameters.
+    callee: String,
+    location: Location,
+    /// Where the call is made when it belongs to a generic function, the
+    /// location then being the one of the call to this function.
+    origin: Option<Location>,
+}
+
+/// Infers the type of every expression of a program and reports the ones
+/// which are not used according to their type.
+///
+/// Unknown functions and variables are left to `validation::validate`, and
+/// only make their uses untyped here.
+pub fn check(ast: &AST) -> Vec<Diagnostic> {
+    let mut checker = Checker {
+        natives: native_functions_map(),
+        functions: HashMap::new(),
+        records: HashMap::new(),
+        constants: HashMap::new(),
+        constraints: HashMap::new(),
+        deferred: Vec::new(),
+        substitutions: Vec::new(),
+        values: HashSet::new(),
+        diagnostics: Vec::new(),
+    };
+
+    checker.check(ast);
+
+    checker.diagnostics
+}
+
+struct Checker<'a> {
+    /// The signatures of native functions, some of them being overloaded.
+    natives: HashMap<&'a str, Vec<Signature>>,
+    /// The signatures of functions of the program, those already checked
+    /// having type parameters for what they accept any type for.
+    functions: HashMap<&'a str, Signature>,
+    /// The fields of each declared record, in declaration order.
+    records: HashMap<&'a str, Vec<(&'a str, Type)>>,
+    constants: HashMap<&'a str, Type>,
+    /// The calls to overloaded functions which generic functions make, in
+    /// terms of their type parameters.
+    constraints: HashMap<&'a str, Vec<Constraint>>,
+    /// The calls to overloaded functions whose overload is yet to be selected.
+    deferred: Vec<Constraint>,
+    /// What each type variable was inferred to be, if anything yet.
+    substitutions: Vec<Option<Type>>,
+    /// The variables standing for values passed to functions. As calls
+    /// evaluate each of their parameters, these cannot be `Unit`.
+    values: HashSet<usize>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Checker<'a> {
+    fn check(&mut self, ast: &'a AST) {
+        // Fields may refer to any record, so every name is known before the
+        // fields are annotated.
+        for statement in &ast.statements {
+            if let Statement::RecordDefinition(record) = statement {
+                self.records.insert(record.name.as_str(), Vec::new());
+            }
+        }
+
+        for statement in &ast.statements {
+            let Statement::RecordDefinition(record) = statement else {
+                continue;
+            };
+
+            let fields = record
+                .fields
+                .iter()
+                .map(|field| (field.name.as_str(), self.annotated(Some(&field.r#type))))
+                .collect();
+            self.records.insert(record.name.as_str(), fields);
+        }
+
+        for statement in &ast.statements {
+            let Statement::FunctionDefinition(function) = statement else {
+                continue;
+            };
+
+            let signature = Signature::new(
+                function
+                    .prototype
+                    .arguments
+                    .iter()
+                    .map(|argument| {
+                        let r#type = self.annotated(argument.r#type.as_ref());
+                        self.value(r#type)
+                    })
+                    .collect(),
+                self.annotated(function.prototype.return_type.as_ref()),
+            );
+            self.functions
+                .insert(function.prototype.name.as_str(), signature);
+        }
+
+        // Constants may refer to each other in any order, so each one has a
+        // type before any value is inferred.
+        for statement in &ast.statements {
+            if let Statement::ConstantDefinition(constant) = statement {
+                let r#type = self.annotated(constant.variable.r#type.as_ref());
+                self.constants
+                    .insert(constant.variable.name.as_str(), r#type);
+            }
+        }
+
+        // A function is checked after those it uses, which are then generic
+        // over what their body does not constrain, as `square(n) { n * n }`
+        // which accepts integers as well as floats.
+        for group in dependency_groups(ast) {
+            for function in &group {
+                self.check_function(function);
+            }
+
+            self.generalize(&group);
+        }
+
+        for statement in &ast.statements {
+            let Statement::ConstantDefinition(constant) = statement else {
+                continue;
+            };
+            let r#type = self.constants[constant.variable.name.as_str()].clone();
+
+            let found = self.infer(&constant.value, &mut HashMap::new());
+            self.expect_annotated(
+                &r#type,
+                &found,
+                value_location(&constant.value),
+                constant.variable.r#type.as_ref(),
+            );
+        }
+
+        self.settle(&HashSet::new());
+    }
+
+    fn check_function(&mut self, function: &'a FunctionDefinition) {
+        let signature = self.functions[function.prototype.name.as_str()].clone();
+
+        let mut scope = function
+            .prototype
+            .arguments
+            .iter()
+            .map(|argument| argument.name.as_str())
+            .zip(signature.parameters)
+            .collect();
+
+        let found = self.infer(&function.body, &mut scope);
+        self.expect_annotated(
+            &signature.result,
+            &found,
+            value_location(&function.body),
+            function.prototype.return_type.as_ref(),
+        );
+    }
+
+    /// Turns what remains to infer in the signatures of a group of functions
+    /// into type parameters, along with the calls to overloaded functions
+    /// they make on these types.
+    fn generalize(&mut self, group: &[&'a FunctionDefinition]) {
+        // What constants are yet to infer is left to their values, and so are
+        // the calls involving it.
+        let mut environment = HashSet::new();
+        for r#type in self.constants.values() {
+            self.variables(r#type, &mut environment);
+        }
+        loop {
+            let before = environment.len();
+
+            for constraint in &self.deferred {
+                let variables = self.constraint_variables(constraint);
+                if !variables.is_disjoint(&environment) {
+                    environment.extend(variables);
+                }
+            }
+
+            if environment.len() == before {
+                break;
+            }
+        }
+
+        let mut generalized = HashSet::new();
+        for function in group {
+            let signature = &self.functions[function.prototype.name.as_str()];
+            for r#type in signature.parameters.iter().chain([&signature.result]) {
+                self.variables(r#type, &mut generalized);
+            }
+        }
+        generalized.retain(|variable| !environment.contains(variable));
+
+        self.settle(&environment.union(&generalized).copied().collect());
+
+        let mut kept = Vec::new();
+        let mut attached = Vec::new();
+        for constraint in std::mem::take(&mut self.deferred) {
+            let variables = self.constraint_variables(&constraint);
+
+            if variables.is_disjoint(&environment) {
+                attached.push((constraint, variables));
+            } else {
+                kept.push(constraint);
+            }
+        }
+        self.deferred = kept;
+
+        for function in group {
+            let name = function.prototype.name.as_str();
+            let signature = self.functions[name].clone();
+
+            let mut variables = HashSet::new();
+            for r#type in signature.parameters.iter().chain([&signature.result]) {
+                self.variables(r#type, &mut variables);
+            }
+
+            let mut parameters = HashMap::new();
+            let generic = Signature::new(
+                signature
+                    .parameters
+                    .iter()
+                    .map(|r#type| self.generic(r#type, &environment, &mut parameters))
+                    .collect(),
+                self.generic(&signature.result, &environment, &mut parameters),
+            );
+
+            let constraints = attached
+                .iter()
+                .filter(|(_, constrained)| !constrained.is_disjoint(&variables))
+                .map(|(constraint, _)| Constraint {
+                    parameters: constraint
+                        .parameters
+                        .iter()
+                        .map(|r#type| self.generic(r#type, &environment, &mut parameters))
+                        .collect(),
+                    result: self.generic(&constraint.result, &environment, &mut parameters),
+                    ..constraint.clone()
+                })
+                .collect();
+
+            self.functions.insert(name, generic);
+            self.constraints.insert(name, constraints);
+        }
+    }
+
+    /// Replaces the variables of a type which are not part of the given
+    /// environment by type parameters.
+    fn generic(
+        &self,
+        r#type: &Type,
+        environment: &HashSet<usize>,
+        parameters: &mut HashMap<usize, usize>,
+    ) -> Type {
+        match self.resolve(r#type) {
+            Type::Variable(variable) if !environment.contains(&variable) => {
+                let next = parameters.len();
+                Type::Parameter(*parameters.entry(variable).or_insert(next))
+            }
+            Type::List(element) => Type::list(self.generic(&element, environment, parameters)),
+            Type::Function(function_parameters, result) => Type::function(
+                function_parameters
+                    .iter()
+                    .map(|parameter| self.generic(parameter, environment, parameters))
+                    .collect(),
+                self.generic(&result, environment, parameters),
+            ),
+            r#type => r#type,
+        }
+    }
+
+    /// The signature of a function of the program for a call to it, along
+    /// with the calls to overloaded functions it makes on the types of its
+    /// parameters, which the call must satisfy once they are inferred.
+    fn instantiate_function(
+        &mut self,
+        name: &str,
+        location: &Location,
+    ) -> (Signature, Vec<Constraint>) {
+        let signature = self.functions[name].clone();
+        let constraints = self.constraints.get(name).cloned().unwrap_or_default();
+
+        let mut parameters = HashMap::new();
+        let instance = Signature::new(
+            signature
+                .parameters
+                .iter()
+                .map(|r#type| {
+                    let r#type = self.instantiate_type(r#type, &mut parameters);
+                    self.value(r#type)
+                })
+                .collect(),
+            self.instantiate_type(&signature.result, &mut parameters),
+        );
+
+        let constraints = constraints
+            .into_iter()
+            .map(|constraint| Constraint {
+                parameters: constraint
+                    .parameters
+                    .iter()
+                    .map(|r#type| self.instantiate_type(r#type, &mut parameters))
+                    .collect(),
+                result: self.instantiate_type(&constraint.result, &mut parameters),
+                location: location.clone(),
+                origin: constraint
+                    .origin
+                    .clone()
+                    .or(Some(constraint.location.clone())),
+                ..constraint
+            })
+            .collect();
+
+        (instance, constraints)
+    }
+
+    /// The type of a variable or, failing that, of a constant.
+    fn value_type(&self, scope: &HashMap<&'a str, Type>, name: &str) -> Option<Type> {
+        scope
+            .get(name)
+            .or_else(|| self.constants.get(name))
+            .cloned()
+    }
+
+    fn infer(&mut self, expression: &'a Expression, scope: &mut HashMap<&'a str, Type>) -> Type {
+        match expression {
+            Expression::Empty => Type::Unit,
+            Expression::Literal(literal) => literal_type(literal),
+            Expression::Variable(variable) => match Callee::lookup(
+                || self.value_type(scope, &variable.name),
+                || {
+                    self.functions
+                        .contains_key(variable.name.as_str())
+                        .then_some(())
+                },
+            ) {
+                Callee::Value(r#type) => r#type,
+                Callee::Function(()) => {
+                    let (signature, constraints) =
+                        self.instantiate_function(&variable.name, &variable.location);
+                    for constraint in constraints {
+                        self.constrain(constraint);
+                    }
+
+                    Type::function(signature.parameters, signature.result)
+                }
+                Callee::Native => self.fresh(),
+            },
+            Expression::Lambda(lambda) => self.infer_lambda(lambda, None, scope),
+            Expression::Let(binding) => {
+                let found = self.infer(&binding.value, scope);
+                let r#type = self.annotated(binding.variable.r#type.as_ref());
+                self.expect_annotated(
+                    &r#type,
+                    &found,
+                    value_location(&binding.value),
+                    binding.variable.r#type.as_ref(),
+                );
+                scope.insert(binding.variable.name.as_str(), r#type);
+
+                Type::Unit
+            }
+            Expression::List(list) => {
+                let element = self.fresh();
+
+                for expression in &list.elements {
+                    let found = self.infer(expression, scope);
+                    self.expect(&element, &found, expression.location());
+                }
+
+                Type::list(element)
+            }
+            Expression::Record(record) => {
+                let mut found = Vec::with_capacity(record.fields.len());
+                for field in &record.fields {
+                    found.push(self.infer(&field.value, scope));
+                }
+
+                let Some((name, fields)) = self.record_of(record) else {
+                    return self.fresh();
+                };
+                let name = name.to_owned();
+
+                for (field, found) in record.fields.iter().zip(&found) {
+                    let (_, expected) =
+                        fields.iter().find(|(name, _)| *name == field.name).unwrap();
+                    let expected = expected.clone();
+                    self.expect(&expected, found, value_location(&field.value));
+                }
+
+                Type::Record(name)
+            }
+            Expression::FieldAccess(access) => {
+                let record = self.infer(&access.record, scope);
+
+                match self.resolve(&record) {
+                    Type::Record(name) => match self.field_type(&name, &access.field) {
+                        Some(r#type) => r#type,
+                        None => {
+                            self.diagnostics.push(
+                                Diagnostic::error(
+                                    Code::UnknownField,
+                                    format!("record `{}` has no field `{}`", name, access.field),
+                                )
+                                .at(access.location.clone()),
+                            );
+
+                            self.fresh()
+                        }
+                    },
+                    Type::Variable(_) => {
+                        // The record is inferred from the field when a single
+                        // record declares it.
+                        let mut declaring = self
+                            .records
+                            .iter()
+                            .filter(|(_, fields)| {
+                                fields.iter().any(|(name, _)| *name == access.field)
+                            })
+                            .map(|(name, _)| name.to_string());
+
+                        match (declaring.next(), declaring.next()) {
+                            (Some(name), None) => {
+                                self.unify(&record, &Type::Record(name.clone()));
+                                self.field_type(&name, &access.field).unwrap()
+                            }
+                            (None, _) => {
+                                self.diagnostics.push(
+                                    Diagnostic::error(
+                                        Code::UnknownField,
+                                        format!("no record has a field `{}`", access.field),
+                                    )
+                                    .at(access.location.clone()),
+                                );
+
+                                self.fresh()
+                            }
+                            _ => self.fresh(),
+                        }
+                    }
+                    found => {
+                        let mut diagnostic = Diagnostic::error(
+                            Code::TypeMismatch,
+                            format!("expected a record, found `{}`", found),
+                        );
+
+                        if let Some(location) = value_location(&access.record) {
+                            diagnostic = diagnostic.at(location.clone());
+                        }
+
+                        self.diagnostics.push(diagnostic);
+
+                        self.fresh()
+                    }
+                }
+            }
+            Expression::Block(block) => {
+                let mut scope = scope.clone();
+
+                let mut r#type = Type::Unit;
+                for expression in &block.expressions {
+                    r#type = self.infer(expression, &mut scope);
+                }
+
+                r#type
+            }
+            Expression::Branch(branch) => {
+                let condition = self.infer(&branch.condition, scope);
+                if !self.unify(&Type::Bool, &condition) {
+                    let mut diagnostic = Diagnostic::error(
+                        Code::NonBooleanCondition,
+                        format!(
+                            "expected a `Bool` condition, found `{}`",
+                            self.resolve(&condition)
+                        ),
+                    )
+                    .with_help("conditions are not converted to booleans, compare the value instead, as in `n != 0`".to_owned());
+
+                    if let Some(location) = value_location(&branch.condition) {
+                        diagnostic = diagnostic.at(location.clone());
+                    }
+
+                    self.diagnostics.push(diagnostic);
+                }
+
+                let then = self.infer(&branch.then, scope);
+
+                if let Expression::Empty = *branch.r#else {
+                    Type::Unit
+                } else {
+                    let r#else = self.infer(&branch.r#else, scope);
+                    self.expect_alike("branches", (&then, &branch.then), (&r#else, &branch.r#else));
+
+                    then
+                }
+            }
+            Expression::Match(r#match) => {
+                let value = self.infer(&r#match.value, scope);
+
+                let mut first: Option<(Type, &Expression)> = None;
+                for arm in &r#match.arms {
+                    if let Pattern::Literal(literal) = &arm.pattern {
+                        self.expect(&value, &literal_type(literal), Some(arm.pattern.location()));
+                    }
+
+                    let body = self.infer(&arm.body, scope);
+                    match &first {
+                        Some((r#type, expression)) => {
+                            self.expect_alike("arms", (r#type, expression), (&body, &arm.body))
+                        }
+                        None => first = Some((body, &arm.body)),
+                    }
+                }
+
+                first.map_or(Type::Unit, |(r#type, _)| r#type)
+            }
+            Expression::FunctionCall(call) => {
+                match call.callee(
+                    || self.value_type(scope, &call.name),
+                    || {
+                        self.functions
+                            .contains_key(call.name.as_str())
+                            .then_some(())
+                    },
+                ) {
+                    Callee::Value(callee) => return self.call_through(call, &callee, scope),
+                    Callee::Function(()) => {
+                        let (signature, constraints) =
+                            self.instantiate_function(&call.name, &call.location);
+                        self.check_parameters(call, &signature.parameters, scope);
+                        for constraint in constraints {
+                            self.constrain(constraint);
+                        }
+
+                        return signature.result;
+                    }
+                    Callee::Native => {}
+                }
+
+                let overloads = self
+                    .natives
+                    .get(call.name.as_str())
+                    .cloned()
+                    .unwrap_or_default();
+
+                match overloads.as_slice() {
+                    [signature] => {
+                        let signature = self.instantiate(signature);
+                        self.check_parameters(call, &signature.parameters, scope);
+
+                        signature.result
+                    }
+                    _ => {
+                        let mut found = Vec::with_capacity(call.parameters.len());
+                        for parameter in &call.parameters {
+                            found.push(self.infer(parameter, scope));
+                        }
+
+                        if overloads.is_empty() {
+                            self.fresh()
+                        } else {
+                            self.select_overload(call, &overloads, &found)
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Infers the type of a lambda. When it is expected to be a function, its
+    /// arguments which are not annotated take the types of its parameters.
+    fn infer_lambda(
+        &mut self,
+        lambda: &'a Lambda,
+        expected: Option<&Type>,
+        scope: &HashMap<&'a str, Type>,
+    ) -> Type {
+        let expected = match expected.map(|expected| self.resolve(expected)) {
+            Some(Type::Function(parameters, _)) if parameters.len() == lambda.arguments.len() => {
+                Some(parameters)
+            }
+            _ => None,
+        };
+
+        let mut scope = scope.clone();
+
+        let mut parameters = Vec::with_capacity(lambda.arguments.len());
+        for (index, argument) in lambda.arguments.iter().enumerate() {
+            let r#type = match (&argument.r#type, &expected) {
+                (None, Some(expected)) => expected[index].clone(),
+                (annotation, _) => {
+                    let r#type = self.annotated(annotation.as_ref());
+                    self.value(r#type)
+                }
+            };
+            scope.insert(argument.name.as_str(), r#type.clone());
+            parameters.push(r#type);
+        }
+
+        let result = self.infer(&lambda.body, &mut scope);
+
+        Type::function(parameters, result)
+    }
+
+    /// Checks the parameters of a call against the types of the parameters
+    /// of the function called. Lambdas come last, so that their arguments get
+    /// the types the other parameters determined, as the element type of the
+    /// list given to `map`.
+    fn check_parameters(
+        &mut self,
+        call: &'a FunctionCall,
+        expected: &[Type],
+        scope: &mut HashMap<&'a str, Type>,
+    ) {
+        let is_lambda = |parameter: &Expression| matches!(parameter, Expression::Lambda(_));
+
+        for (index, parameter) in call.parameters.iter().enumerate() {
+            if is_lambda(parameter) {
+                continue;
+            }
+
+            let found = self.infer(parameter, scope);
+            if let Some(expected) = expected.get(index) {
+                self.expect(expected, &found, parameter.location());
+            }
+        }
+
+        for (index, parameter) in call.parameters.iter().enumerate() {
+            let Expression::Lambda(lambda) = parameter else {
+                continue;
+            };
+
+            let found = self.infer_lambda(lambda, expected.get(index), scope);
+            if let Some(expected) = expected.get(index) {
+                self.expect(expected, &found, parameter.location());
+            }
+        }
+    }
+
+    /// Returns the result of calling a variable, which must hold a function
+    /// accepting the parameters of the call.
+    fn call_through(
+        &mut self,
+        call: &'a FunctionCall,
+        callee: &Type,
+        scope: &mut HashMap<&'a str, Type>,
+    ) -> Type {
+        match self.resolve(callee) {
+            Type::Function(parameters, result) if parameters.len() == call.parameters.len() => {
+                self.check_parameters(call, &parameters, scope);
+
+                *result
+            }
+            Type::Variable(_) => {
+                let mut found = Vec::with_capacity(call.parameters.len());
+                for parameter in &call.parameters {
+                    found.push(self.infer(parameter, scope));
+                }
+
+                let result = self.fresh();
+                self.unify(callee, &Type::function(found, result.clone()));
+
+                result
+            }
+            callee => {
+                for parameter in &call.parameters {
+                    self.infer(parameter, scope);
+                }
+
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        Code::TypeMismatch,
+                        format!(
+                            "expected a function accepting {} parameters, found `{}`",
+                            call.parameters.len(),
+                            callee
+                        ),
+                    )
+                    .at(call.location.clone()),
+                );
+
+                self.fresh()
+            }
+        }
+    }
+
+    /// Returns the result of the overload accepting the parameters of a call.
+    /// It is selected once they are inferred enough to tell which one it is.
+    fn select_overload(
+        &mut self,
+        call: &FunctionCall,
+        overloads: &[Signature],
+        found: &[Type],
+    ) -> Type {
+        let result = self.fresh();
+
+        self.constrain(Constraint {
+            overloads: overloads.to_vec(),
+            parameters: found.to_vec(),
+            result: result.clone(),
+            callee: match call.operator {
+                Some(operator) => format!("operator `{}`", operator.symbol()),
+                None => format!("function \"{}\"", call.name),
+            },
+            location: call.location.clone(),
+            origin: None,
+        });
+
+        result
+    }
+
+    /// Selects the overload of a call when a single one accepts its
+    /// parameters, or defers it to when more is inferred.
+    fn constrain(&mut self, constraint: Constraint) {
+        match self.accepting(&constraint).as_slice() {
+            [] => self.report_overloads(&constraint),
+            [overload] => {
+                self.accepts(&constraint, *overload);
+            }
+            _ => self.deferred.push(constraint),
+        }
+    }
+
+    /// Selects the overload of the deferred calls which only one accepts,
+    /// until none is left.
+    fn solve(&mut self) {
+        loop {
+            let mut progressed = false;
+
+            for constraint in std::mem::take(&mut self.deferred) {
+                match self.accepting(&constraint).as_slice() {
+                    [] => self.report_overloads(&constraint),
+                    [overload] => {
+                        self.accepts(&constraint, *overload);
+                        progressed = true;
+                    }
+                    _ => self.deferred.push(constraint),
+                }
+            }
+
+            if !progressed {
+                break;
+            }
+        }
+    }
+
+    /// Selects an overload for every deferred call which does not involve
+    /// the given variables. When nothing tells which one, the first one
+    /// accepting the parameters is.
+    fn settle(&mut self, kept: &HashSet<usize>) {
+        self.solve();
+
+        while let Some(position) = self
+            .deferred
+            .iter()
+            .position(|constraint| self.constraint_variables(constraint).is_disjoint(kept))
+        {
+            let constraint = self.deferred.remove(position);
+            match self.accepting(&constraint).first() {
+                Some(overload) => {
+                    self.accepts(&constraint, *overload);
+                }
+                None => self.report_overloads(&constraint),
+            }
+
+            self.solve();
+        }
+    }
+
+    /// The overloads which accept the parameters of a call.
+    fn accepting(&mut self, constraint: &Constraint) -> Vec<usize> {
+        (0..constraint.overloads.len())
+            .filter(|overload| {
+                // Trying an overload must not leave any inferred variable
+                // behind.
+                let substitutions = self.substitutions.clone();
+                let values = self.values.clone();
+                let accepted = self.accepts(constraint, *overload);
+                self.substitutions = substitutions;
+                self.values = values;
+
+                accepted
+            })
+            .collect()
+    }
+
+    /// Unifies the types of a call with those of an overload, telling
+    /// whether it was possible.
+    fn accepts(&mut self, constraint: &Constraint, overload: usize) -> bool {
+        let signature = self.instantiate(&constraint.overloads[overload]);
+
+        signature.parameters.len() == constraint.parameters.len()
+            && signature
+                .parameters
+                .iter()
+                .zip(&constraint.parameters)
+                .all(|(expected, found)| self.unify(expected, found))
+            && self.unify(&signature.result, &constraint.result)
+    }
+
+    fn report_overloads(&mut self, constraint: &Constraint) {
+        // Calls with the wrong number of parameters are reported by
+        // `validation::validate`.
+        if constraint
+            .overloads
+            .iter()
+            .all(|overload| overload.parameters.len() != constraint.parameters.len())
+        {
+            return;
+        }
+
+        let mut diagnostic = Diagnostic::error(
+            Code::NoMatchingSignature,
+            format!(
+                "{} does not accept ({})",
+                constraint.callee,
+                constraint
+                    .parameters
+                    .iter()
+                    .map(|r#type| self.resolve(r#type).to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        )
+        .at(constraint.location.clone())
+        .with_help(format!(
+            "available signatures are {}",
+            constraint
+                .overloads
+                .iter()
+                .map(|signature| format!("`{}`", signature))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ));
+
+        if let Some(origin) = &constraint.origin {
+            diagnostic = diagnostic.with_label(
+                origin.clone(),
+                format!("{} is called here", constraint.callee),
+            );
+        }
+
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// The variables a call to an overloaded function is yet to infer.
+    fn constraint_variables(&self, constraint: &Constraint) -> HashSet<usize> {
+        let mut variables = HashSet::new();
+        for r#type in constraint.parameters.iter().chain([&constraint.result]) {
+            self.variables(r#type, &mut variables);
+        }
+
+        variables
+    }
+
+    /// Collects the variables a type is yet to infer.
+    fn variables(&self, r#type: &Type, variables: &mut HashSet<usize>) {
+        match self.resolve(r#type) {
+            Type::Variable(variable) => {
+                variables.insert(variable);
+            }
+            Type::List(element) => self.variables(&element, variables),
+            Type::Function(parameters, result) => {
+                for parameter in &parameters {
+                    self.variables(parameter, variables);
+                }
+                self.variables(&result, variables);
+            }
+            _ => {}
+        }
+    }
+
+    /// Reports alternative expressions, such as the branches of an `if`,
+    /// which cannot have the same type.
+    fn expect_alike(
+        &mut self,
+        alternatives: &str,
+        (first, first_expression): (&Type, &Expression),
+        (other, other_expression): (&Type, &Expression),
+    ) {
+        if !self.unify(first, other) {
+            let mut diagnostic = Diagnostic::error(
+                Code::TypeMismatch,
+                format!(
+                    "{} have different types: `{}` and `{}`",
+                    alternatives,
+                    self.resolve(first),
+                    self.resolve(other)
+                ),
+            );
+
+            if let Some(location) = value_location(other_expression) {
+                diagnostic = diagnostic.at(location.clone());
+            }
+            if let Some(location) = value_location(first_expression) {
+                diagnostic = diagnostic.with_label(
+                    location.clone(),
+                    format!("this is of type `{}`", self.resolve(first)),
+                );
+            }
+
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    /// Reports a mismatch unless the found type can be the expected one.
+    fn expect(&mut self, expected: &Type, found: &Type, location: Option<&Location>) {
+        self.expect_annotated(expected, found, location, None)
+    }
+
+    /// Same as `expect`, pointing at the annotation the expected type comes
+    /// from, if any.
+    fn expect_annotated(
+        &mut self,
+        expected: &Type,
+        found: &Type,
+        location: Option<&Location>,
+        annotation: Option<&TypeAnnotation>,
+    ) {
+        if !self.unify(expected, found) {
+            let mut diagnostic = match (self.resolve(expected), self.resolve(found)) {
+                (Type::Variable(_), Type::Unit) => Diagnostic::error(
+                    Code::TypeMismatch,
+                    "expected a value, found `Unit`".to_owned(),
+                )
+                .with_help("this produces no value to pass on".to_owned()),
+                (expected, found) => Diagnostic::error(
+                    Code::TypeMismatch,
+                    format!("expected `{}`, found `{}`", expected, found),
+                ),
+            };
+
+            if let Some(location) = location {
+                diagnostic = diagnostic.at(location.clone());
+            }
+            if let Some(annotation) = annotation {
+                diagnostic = diagnostic.with_label(
+                    annotation.location().clone(),
+                    "expected because of this annotation".to_owned(),
+                );
+            }
+
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    /// Makes two types equal by inferring their variables, telling whether it
+    /// was possible.
+    fn unify(&mut self, a: &Type, b: &Type) -> bool {
+        match (self.resolve(a), self.resolve(b)) {
+            (Type::Variable(a), Type::Variable(b)) if a == b => true,
+            (Type::Variable(variable), r#type) | (r#type, Type::Variable(variable)) => {
+                // A type cannot contain itself.
+                if r#type.contains(variable) {
+                    return false;
+                }
+
+                if self.values.contains(&variable) {
+                    match r#type {
+                        Type::Unit => return false,
+                        Type::Variable(other) => {
+                            self.values.insert(other);
+                        }
+                        _ => {}
+                    }
+                }
+
+                self.substitutions[variable] = Some(r#type);
+                true
+            }
+            (Type::List(a), Type::List(b)) => self.unify(&a, &b),
+            (Type::Function(a_parameters, a_result), Type::Function(b_parameters, b_result)) => {
+                a_parameters.len() == b_parameters.len()
+                    && a_parameters
+                        .iter()
+                        .zip(&b_parameters)
+                        .all(|(a, b)| self.unify(a, b))
+                    && self.unify(&a_result, &b_result)
+            }
+            (a, b) => a == b,
+        }
+    }
+
+    /// Replaces the inferred variables of a type by what they stand for.
+    fn resolve(&self, r#type: &Type) -> Type {
+        match r#type {
+            Type::Variable(variable) => match &self.substitutions[*variable] {
+                Some(r#type) => self.resolve(r#type),
+                None => r#type.clone(),
+            },
+            Type::List(element) => Type::list(self.resolve(element)),
+            Type::Function(parameters, result) => Type::function(
+                parameters
+                    .iter()
+                    .map(|parameter| self.resolve(parameter))
+                    .collect(),
+                self.resolve(result),
+            ),
+            _ => r#type.clone(),
+        }
+    }
+
+    /// Replaces the type parameters of a native signature by fresh
+    /// variables, so each call infers them on its own. Natives only deal with
+    /// values, which their type parameters stand for.
+    fn instantiate(&mut self, signature: &Signature) -> Signature {
+        let mut parameters = HashMap::new();
+
+        let instance = Signature::new(
+            signature
+                .parameters
+                .iter()
+                .map(|r#type| self.instantiate_type(r#type, &mut parameters))
+                .collect(),
+            self.instantiate_type(&signature.result, &mut parameters),
+        );
+
+        for r#type in parameters.into_values() {
+            self.value(r#type);
+        }
+
+        instance
+    }
+
+    /// Marks a type as the one of a value passed to a function, which
+    /// therefore cannot be `Unit`.
+    fn value(&mut self, r#type: Type) -> Type {
+        if let Type::Variable(variable) = self.resolve(&r#type) {
+            self.values.insert(variable);
+        }
+
+        r#type
+    }
+
+    fn instantiate_type(&mut self, r#type: &Type, parameters: &mut HashMap<usize, Type>) -> Type {
+        match r#type {
+            Type::Parameter(parameter) => parameters
+                .entry(*parameter)
+                .or_insert_with(|| self.fresh())
+                .clone(),
+            Type::List(element) => Type::list(self.instantiate_type(element, parameters)),
+            Type::Function(function_parameters, result) => Type::function(
+                function_parameters
+                    .iter()
+                    .map(|parameter| self.instantiate_type(parameter, parameters))
+                    .collect(),
+                self.instantiate_type(result, parameters),
+            ),
+            _ => r#type.clone(),
+        }
+    }
+
+    /// The type an annotation stands for, or a type to infer when there is
+    /// none. Unknown type names are left to `validation::validate`.
+    fn annotated(&mut self, annotation: Option<&TypeAnnotation>) -> Type {
+        let annotated = annotation.and_then(|annotation| {
+            Type::annotated(annotation, &|name| self.records.contains_key(name))
+        });
+
+        match annotated {
+            Some(r#type) => r#type,
+            None => self.fresh(),
+        }
+    }
+
+    /// The declared record a literal builds, which is the only one having
+    /// exactly its fields. Validation reports literals matching none or
+    /// several of them.
+    fn record_of(&self, record: &Record) -> Option<(&'a str, Vec<(&'a str, Type)>)> {
+        let mut candidates = self.records.iter().filter(|(_, fields)| {
+            fields.len() == record.fields.len()
+                && record
+                    .fields
+                    .iter()
+                    .all(|field| fields.iter().any(|(name, _)| *name == field.name))
+        });
+
+        match (candidates.next(), candidates.next()) {
+            (Some((name, fields)), None) => Some((*name, fields.clone())),
+            _ => None,
+        }
+    }
+
+    fn field_type(&self, record: &str, field: &str) -> Option<Type> {
+        self.records
+            .get(record)?
+            .iter()
+            .find(|(name, _)| *name == field)
+            .map(|(_, r#type)| r#type.clone())
+    }
+
+    fn fresh(&mut self) -> Type {
+        self.substitutions.push(None);
+        Type::Variable(self.substitutions.len() - 1)
+    }
+}
+
+impl Type {
+    fn contains(&self, variable: usize) -> bool {
+        match self {
+            Type::Variable(other) => *other == variable,
+            Type::List(element) => element.contains(variable),
+            Type::Function(parameters, result) => {
+                parameters
+                    .iter()
+                    .any(|parameter| parameter.contains(variable))
+                    || result.contains(variable)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn literal_type(literal: &Literal) -> Type {
+    match literal {
+        Literal::Bool(_) => Type::Bool,
+        Literal::Number(_) => Type::Int,
+        Literal::Float(_) => Type::Float,
+        Literal::String(_) => Type::String,
+    }
+}
+
+/// The location of the expression giving its value to another one.
+fn value_location(expression: &Expression) -> Option<&Location> {
+    match expression {
+        Expression::Block(block) => match block.expressions.last() {
+            Some(last) => value_location(last),
+            None => Some(&block.location),
+        },
+        _ => expression.location(),
+    }
+}
+
+/// Groups the functions of a program which use each other, directly or not,
+/// ordered so that a group only uses functions of the groups before it.
+fn dependency_groups(ast: &AST) -> Vec<Vec<&FunctionDefinition>> {
+    let mut grouping = Grouping {
+        functions: Vec::new(),
+        indices: HashMap::new(),
+        visits: HashMap::new(),
+        stack: Vec::new(),
+        groups: Vec::new(),
+    };
+
+    for statement in &ast.statements {
+        if let Statement::FunctionDefinition(function) = statement {
+            grouping
+                .indices
+                .insert(function.prototype.name.as_str(), grouping.functions.len());
+            grouping.functions.push(function.as_ref());
+        }
+    }
+
+    for function in 0..grouping.functions.len() {
+        if !grouping.visits.contains_key(&function) {
+            grouping.visit(function);
+        }
+    }
+
+    grouping.groups
+}
+
+/// Finds the strongly connected components of the graph of functions using
+/// each other, following Tarjan's algorithm.
+struct Grouping<'a> {
+    functions: Vec<&'a FunctionDefinition>,
+    /// The position of each function, by name.
+    indices: HashMap<&'a str, usize>,
+    /// When each function visited was reached, and the earliest function
+    /// on the stack it leads to.
+    visits: HashMap<usize, (usize, usize)>,
+    stack: Vec<usize>,
+    groups: Vec<Vec<&'a FunctionDefinition>>,
+}
+
+impl<'a> Grouping<'a> {
+    fn visit(&mut self, function: usize) {
+        let order = self.visits.len();
+        self.visits.insert(function, (order, order));
+        self.stack.push(function);
+
+        let definition = self.functions[function];
+        let mut bound = definition
+            .prototype
+            .arguments
+            .iter()
+            .map(|argument| argument.name.as_str())
+            .collect();
+        let mut names = Vec::new();
+        definition.body.references(&mut bound, &mut names);
+
+        for name in names {
+            let Some(&used) = self.indices.get(name) else {
+                continue;
+            };
+
+            let reached = match self.visits.get(&used) {
+                None => {
+                    self.visit(used);
+                    self.visits[&used].1
+                }
+                Some(&(order, _)) if self.stack.contains(&used) => order,
+                Some(_) => continue,
+            };
+
+            let visit = self.visits.get_mut(&function).unwrap();
+            visit.1 = visit.1.min(reached);
+        }
+
+        let (order, earliest) = self.visits[&function];
+        if order == earliest {
+            let start = self
+                .stack
+                .iter()
+                .position(|other| *other == function)
+                .unwrap();
+            let mut group: Vec<usize> = self.stack.split_off(start);
+            group.sort_unstable();
+
+            self.groups.push(
+                group
+                    .into_iter()
+                    .map(|index| self.functions[index])
+                    .collect(),
+            );
+        }
+    }
+}
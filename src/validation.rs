@@ -1,187 +1,848 @@
 use std::collections::HashMap;
 
-use super::ast::{Expression, Statement, VariableDefinition, AST};
+use super::ast::{
+    Callee, Expression, Literal, Pattern, RecordDefinition, Statement, TypeAnnotation,
+    VariableDefinition, AST,
+};
+use super::diagnostics::{Code, Diagnostic};
 use super::lexing::Location;
-use super::native::native_functions_map;
+use super::native::{native_functions_map, INPUT_OUTPUT};
+use super::typing::{Signature, Type};
 
-#[derive(Debug)]
-pub struct Report {
-    pub issue: String,
-    pub location: Location,
-}
-
-impl std::fmt::Display for Report {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(
-            f,
-            "{}:{}:{} {}",
-            self.location.path, self.location.line, self.location.column, self.issue
-        )
-    }
-}
-
-pub fn validate(ast: &AST) -> Vec<Report> {
+pub fn validate(ast: &AST) -> Vec<Diagnostic> {
     let mut prototypes: HashMap<&String, (Location, &[VariableDefinition], usize)> = HashMap::new();
-    let native_functions: HashMap<&str, Vec<VariableDefinition>> = native_functions_map();
+    let native_functions: HashMap<&str, Vec<Signature>> = native_functions_map();
+    let records: Vec<&RecordDefinition> = ast
+        .statements
+        .iter()
+        .filter_map(|statement| match statement {
+            Statement::RecordDefinition(record) => Some(record),
+            _ => None,
+        })
+        .collect();
 
     for statement in &ast.statements {
-        let Statement::FunctionDefinition(definition) = statement;
+        let Statement::FunctionDefinition(definition) = statement else {
+            continue;
+        };
         prototypes.insert(
             &definition.prototype.name,
             (
-                definition.location.clone(),
+                definition.prototype.location.clone(),
                 &definition.prototype.arguments,
                 0,
             ),
         );
     }
 
-    let mut reports = Vec::new();
+    let mut constants: HashMap<&String, (Location, usize)> = HashMap::new();
+    for statement in &ast.statements {
+        if let Statement::ConstantDefinition(constant) = statement {
+            constants.insert(
+                &constant.variable.name,
+                (constant.variable.location.clone(), 0),
+            );
+        }
+    }
+
+    let dependencies = dependencies(ast);
+    let mut diagnostics = validate_constant_cycles(ast, &dependencies);
+    diagnostics.append(&mut validate_constant_purity(ast, &dependencies));
+
+    for record in &records {
+        diagnostics.extend(validate_fields(
+            record
+                .fields
+                .iter()
+                .map(|field| (&field.name, &field.location)),
+        ));
+
+        for field in &record.fields {
+            diagnostics.extend(validate_type_annotation(&field.r#type, &records));
+        }
+    }
+
+    for statement in &ast.statements {
+        let Statement::ConstantDefinition(constant) = statement else {
+            continue;
+        };
+
+        if let Some(annotation) = &constant.variable.r#type {
+            diagnostics.extend(validate_type_annotation(annotation, &records));
+        }
+
+        diagnostics.append(&mut validate_expression(
+            &mut prototypes,
+            &native_functions,
+            &records,
+            &mut constants,
+            &mut HashMap::new(),
+            &constant.value,
+        ));
+    }
 
     for statement in &ast.statements {
-        let Statement::FunctionDefinition(definition) = statement;
+        let Statement::FunctionDefinition(definition) = statement else {
+            continue;
+        };
         let mut variables: HashMap<&String, (Location, usize)> = HashMap::new();
 
+        for annotation in definition
+            .prototype
+            .arguments
+            .iter()
+            .filter_map(|arg| arg.r#type.as_ref())
+            .chain(&definition.prototype.return_type)
+        {
+            diagnostics.extend(validate_type_annotation(annotation, &records));
+        }
+
         for arg in &definition.prototype.arguments {
-            if variables.contains_key(&arg.name) {
-                reports.push(Report {
-                    location: arg.location.clone(),
-                    issue: format!(
-                        "several \"{}\"'s arguments have the name \"{}\"",
-                        definition.prototype.name, arg.name
-                    ),
-                });
+            if let Some((previous, _)) = variables.get(&arg.name) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        Code::DuplicatedArgument,
+                        format!(
+                            "several \"{}\"'s arguments have the name \"{}\"",
+                            definition.prototype.name, arg.name
+                        ),
+                    )
+                    .at(arg.location.clone())
+                    .with_label(previous.clone(), "first defined here".to_owned()),
+                );
             } else {
                 variables.insert(&arg.name, (arg.location.clone(), 0));
             }
         }
 
-        reports.append(&mut validate_expression(
+        diagnostics.append(&mut validate_expression(
             &mut prototypes,
             &native_functions,
+            &records,
+            &mut constants,
             &mut variables,
             &definition.body,
         ));
 
         for (variable, (location, count)) in variables {
             if count == 0 {
-                reports.push(Report {
-                    location: location.clone(),
-                    issue: format!("variable \"{}\" is never used", variable),
-                });
+                diagnostics.push(
+                    Diagnostic::warning(
+                        Code::UnusedVariable,
+                        format!("variable \"{}\" is never used", variable),
+                    )
+                    .at(location),
+                );
             }
         }
     }
 
+    for (constant, (location, count)) in constants {
+        if !constant.contains('.') && count == 0 {
+            diagnostics.push(
+                Diagnostic::warning(
+                    Code::UnusedVariable,
+                    format!("constant \"{}\" is never used", constant),
+                )
+                .at(location),
+            );
+        }
+    }
+
     for (function, (location, _, count)) in &prototypes {
-        if *function != "main" && *count == 0 {
-            reports.push(Report {
-                issue: format!("function \"{}\" is never used", function),
-                location: location.clone(),
-            });
+        // Functions of imported modules are meant to be shared, so programs
+        // only use some of them.
+        if *function != "main" && !function.contains('.') && *count == 0 {
+            diagnostics.push(
+                Diagnostic::warning(
+                    Code::UnusedFunction,
+                    format!("function \"{}\" is never used", function),
+                )
+                .at(location.clone()),
+            );
         }
     }
 
-    reports
+    diagnostics
 }
 
-fn validate_expression(
-    prototypes: &mut HashMap<&String, (Location, &[VariableDefinition], usize)>,
-    native_functions: &HashMap<&str, Vec<VariableDefinition>>,
-    variables: &mut HashMap<&String, (Location, usize)>,
-    expression: &Expression,
-) -> Vec<Report> {
+fn validate_expression<'a>(
+    prototypes: &mut HashMap<&'a String, (Location, &'a [VariableDefinition], usize)>,
+    native_functions: &HashMap<&str, Vec<Signature>>,
+    records: &[&'a RecordDefinition],
+    constants: &mut HashMap<&'a String, (Location, usize)>,
+    variables: &mut HashMap<&'a String, (Location, usize)>,
+    expression: &'a Expression,
+) -> Vec<Diagnostic> {
     match expression {
         Expression::FunctionCall(call) => {
-            let mut reports = Vec::<Report>::new();
+            let mut diagnostics = Vec::<Diagnostic>::new();
+
+            let callee = call.callee(
+                || {
+                    variables
+                        .get_mut(&call.name)
+                        .or_else(|| constants.get_mut(&call.name))
+                },
+                || prototypes.get_mut(&call.name),
+            );
 
-            if let Some((_, arguments, count)) = prototypes.get_mut(&call.name) {
+            // How many parameters functions held by variables accept is left
+            // to `typing::check`.
+            if let Callee::Value((_, count)) = callee {
+                *count += 1;
+            } else if let Callee::Function((location, arguments, count)) = callee {
                 *count += 1;
                 if arguments.len() != call.parameters.len() {
-                    reports.push(Report {
-                        issue: format!(
-                            "function \"{}\" accepts {} arguments but received {} parameters",
-                            call.name,
-                            arguments.len(),
-                            call.parameters.len()
-                        ),
-                        location: call.location.clone(),
-                    });
+                    diagnostics.push(
+                        Diagnostic::error(
+                            Code::ArgumentsCount,
+                            format!(
+                                "function \"{}\" accepts {} arguments but received {} parameters",
+                                call.name,
+                                arguments.len(),
+                                call.parameters.len()
+                            ),
+                        )
+                        .at(call.location.clone())
+                        .with_label(location.clone(), "function defined here".to_owned()),
+                    );
                 }
-            } else if let Some(arguments) = native_functions.get(call.name.as_str()) {
-                if arguments.len() != call.parameters.len() {
-                    reports.push(Report {
-                        issue: format!(
-                            "function \"{}\" accepts {} arguments but received {} parameters",
-                            call.name,
-                            arguments.len(),
-                            call.parameters.len()
-                        ),
-                        location: call.location.clone(),
-                    });
+            } else if let Some(overloads) = native_functions.get(call.name.as_str()) {
+                // Overloads of a native function all share the same arity.
+                let signature = &overloads[0];
+                if signature.parameters.len() != call.parameters.len() {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            Code::ArgumentsCount,
+                            format!(
+                                "function \"{}\" accepts {} arguments but received {} parameters",
+                                call.name,
+                                signature.parameters.len(),
+                                call.parameters.len()
+                            ),
+                        )
+                        .at(call.location.clone()),
+                    );
                 }
             } else {
-                reports.push(Report {
-                    issue: format!("unknwon function \"{}\"", call.name),
-                    location: call.location.clone(),
-                })
+                diagnostics.push(
+                    Diagnostic::error(
+                        Code::UnknownFunction,
+                        format!("unknwon function \"{}\"", call.name),
+                    )
+                    .at(call.location.clone()),
+                )
             }
 
             for parameter in &call.parameters {
-                reports.append(&mut validate_expression(
+                diagnostics.append(&mut validate_expression(
                     prototypes,
                     native_functions,
+                    records,
+                    constants,
                     variables,
                     parameter,
                 ));
             }
 
-            reports
+            diagnostics
         }
         Expression::Branch(branch) => {
-            let mut reports = Vec::new();
+            let mut diagnostics = Vec::new();
 
             if let Expression::Empty = *branch.condition {
-                reports.push(Report {
-                    issue: "empty branch condition".to_string(),
-                    location: branch.location.clone(),
-                });
+                diagnostics.push(
+                    Diagnostic::error(Code::EmptyCondition, "empty branch condition".to_string())
+                        .at(branch.location.clone()),
+                );
             } else {
-                reports.append(&mut validate_expression(
+                diagnostics.append(&mut validate_expression(
                     prototypes,
                     native_functions,
+                    records,
+                    constants,
                     variables,
                     &branch.condition,
                 ));
             }
 
-            reports.append(&mut validate_expression(
+            diagnostics.append(&mut validate_expression(
                 prototypes,
                 native_functions,
+                records,
+                constants,
                 variables,
                 &branch.then,
             ));
 
-            reports.append(&mut validate_expression(
+            diagnostics.append(&mut validate_expression(
                 prototypes,
                 native_functions,
+                records,
+                constants,
                 variables,
                 &branch.r#else,
             ));
 
-            reports
+            diagnostics
+        }
+        Expression::Match(r#match) => {
+            let mut diagnostics = validate_expression(
+                prototypes,
+                native_functions,
+                records,
+                constants,
+                variables,
+                &r#match.value,
+            );
+
+            let mut wildcard = None;
+            let mut literals: Vec<&Literal> = Vec::new();
+
+            for arm in &r#match.arms {
+                let previous = match &arm.pattern {
+                    Pattern::Wildcard(location) => {
+                        let previous = wildcard;
+                        wildcard.get_or_insert(location);
+                        previous
+                    }
+                    Pattern::Literal(literal) => wildcard.or_else(|| {
+                        literals
+                            .iter()
+                            .find(|previous| same_literal(previous, literal))
+                            .map(|previous| previous.location())
+                    }),
+                };
+
+                match previous {
+                    Some(location) => diagnostics.push(
+                        Diagnostic::warning(
+                            Code::UnreachablePattern,
+                            "this arm is never reached".to_owned(),
+                        )
+                        .at(arm.pattern.location().clone())
+                        .with_label(
+                            location.clone(),
+                            "values are already matched here".to_owned(),
+                        ),
+                    ),
+                    None => {
+                        if let Pattern::Literal(literal) = &arm.pattern {
+                            literals.push(literal);
+                        }
+                    }
+                }
+
+                diagnostics.append(&mut validate_expression(
+                    prototypes,
+                    native_functions,
+                    records,
+                    constants,
+                    variables,
+                    &arm.body,
+                ));
+            }
+
+            let booleans_covered = [true, false].iter().all(|value| {
+                literals.iter().any(
+                    |literal| matches!(literal, Literal::Bool(boolean) if boolean.value == *value),
+                )
+            });
+
+            if wildcard.is_none() && !booleans_covered {
+                diagnostics.push(
+                    Diagnostic::warning(
+                        Code::NonExhaustiveMatch,
+                        "match does not cover every value".to_owned(),
+                    )
+                    .at(r#match.location.clone())
+                    .with_help("add a `_ => ...` arm, or running the program may fail".to_owned()),
+                );
+            }
+
+            diagnostics
+        }
+        Expression::Block(block) => {
+            let mut diagnostics = Vec::new();
+
+            if let Some((_, discarded)) = block.expressions.split_last() {
+                for expression in discarded {
+                    if let Expression::Variable(_) | Expression::Literal(_) | Expression::List(_) =
+                        expression
+                    {
+                        diagnostics.push(
+                            Diagnostic::warning(
+                                Code::UnusedValue,
+                                "the value of this expression is never used".to_owned(),
+                            )
+                            .at(expression.location().unwrap().clone()),
+                        );
+                    }
+                }
+            }
+
+            // Variables bound in this block, along with the definitions they
+            // shadow.
+            let mut bindings = Vec::new();
+
+            for expression in &block.expressions {
+                diagnostics.append(&mut validate_expression(
+                    prototypes,
+                    native_functions,
+                    records,
+                    constants,
+                    variables,
+                    expression,
+                ));
+
+                if let Expression::Let(binding) = expression {
+                    let shadowed = variables.insert(
+                        &binding.variable.name,
+                        (binding.variable.location.clone(), 0),
+                    );
+
+                    if let Some((location, _)) = &shadowed {
+                        diagnostics.push(
+                            Diagnostic::warning(
+                                Code::ShadowedVariable,
+                                format!(
+                                    "variable \"{}\" shadows a previous definition",
+                                    binding.variable.name
+                                ),
+                            )
+                            .at(binding.variable.location.clone())
+                            .with_label(location.clone(), "previously defined here".to_owned()),
+                        );
+                    }
+
+                    bindings.push((&binding.variable.name, shadowed));
+                }
+            }
+
+            for (name, shadowed) in bindings.into_iter().rev() {
+                if let Some((location, 0)) = variables.remove(name) {
+                    diagnostics.push(
+                        Diagnostic::warning(
+                            Code::UnusedVariable,
+                            format!("variable \"{}\" is never used", name),
+                        )
+                        .at(location),
+                    );
+                }
+
+                if let Some(shadowed) = shadowed {
+                    variables.insert(name, shadowed);
+                }
+            }
+
+            diagnostics
+        }
+        Expression::Let(binding) => {
+            let mut diagnostics = validate_expression(
+                prototypes,
+                native_functions,
+                records,
+                constants,
+                variables,
+                &binding.value,
+            );
+
+            if let Some(annotation) = &binding.variable.r#type {
+                diagnostics.extend(validate_type_annotation(annotation, records));
+            }
+
+            diagnostics
+        }
+        Expression::List(list) => {
+            let mut diagnostics = Vec::new();
+
+            for element in &list.elements {
+                diagnostics.append(&mut validate_expression(
+                    prototypes,
+                    native_functions,
+                    records,
+                    constants,
+                    variables,
+                    element,
+                ));
+            }
+
+            diagnostics
+        }
+        Expression::Record(record) => {
+            let mut diagnostics = validate_fields(
+                record
+                    .fields
+                    .iter()
+                    .map(|field| (&field.name, &field.location)),
+            );
+
+            let candidates: Vec<&str> = records
+                .iter()
+                .filter(|declaration| {
+                    declaration.fields.len() == record.fields.len()
+                        && record.fields.iter().all(|field| {
+                            declaration
+                                .fields
+                                .iter()
+                                .any(|declared| declared.name == field.name)
+                        })
+                })
+                .map(|declaration| declaration.name.as_str())
+                .collect();
+
+            if candidates.len() > 1 {
+                diagnostics.push(
+                    Diagnostic::error(
+                        Code::AmbiguousRecord,
+                        format!(
+                            "records {} all have the fields {}",
+                            candidates
+                                .iter()
+                                .map(|name| format!("\"{}\"", name))
+                                .collect::<Vec<String>>()
+                                .join(", "),
+                            record
+                                .fields
+                                .iter()
+                                .map(|field| format!("\"{}\"", field.name))
+                                .collect::<Vec<String>>()
+                                .join(", ")
+                        ),
+                    )
+                    .at(record.location.clone())
+                    .with_help("give one of them a field the others do not have".to_owned()),
+                );
+            }
+
+            if candidates.is_empty() {
+                diagnostics.push(
+                    Diagnostic::error(
+                        Code::UnknownRecord,
+                        format!(
+                            "no record has the fields {}",
+                            record
+                                .fields
+                                .iter()
+                                .map(|field| format!("\"{}\"", field.name))
+                                .collect::<Vec<String>>()
+                                .join(", ")
+                        ),
+                    )
+                    .at(record.location.clone())
+                    .with_help("declare one with `record Name { field: Type }`".to_owned()),
+                );
+            }
+
+            for field in &record.fields {
+                diagnostics.append(&mut validate_expression(
+                    prototypes,
+                    native_functions,
+                    records,
+                    constants,
+                    variables,
+                    &field.value,
+                ));
+            }
+
+            diagnostics
+        }
+        // Fields are checked along with the type of the record, by
+        // `typing::check`.
+        Expression::FieldAccess(access) => validate_expression(
+            prototypes,
+            native_functions,
+            records,
+            constants,
+            variables,
+            &access.record,
+        ),
+        Expression::Lambda(lambda) => {
+            let mut diagnostics = Vec::new();
+
+            // Arguments along with the variables they shadow while the body
+            // is validated.
+            let mut bindings: Vec<(&String, Option<(Location, usize)>)> = Vec::new();
+
+            for argument in &lambda.arguments {
+                if let Some(annotation) = &argument.r#type {
+                    diagnostics.extend(validate_type_annotation(annotation, records));
+                }
+
+                if bindings.iter().any(|(name, _)| **name == argument.name) {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            Code::DuplicatedArgument,
+                            format!(
+                                "several lambda's arguments have the name \"{}\"",
+                                argument.name
+                            ),
+                        )
+                        .at(argument.location.clone()),
+                    );
+                } else {
+                    let shadowed = variables.insert(&argument.name, (argument.location.clone(), 0));
+                    bindings.push((&argument.name, shadowed));
+                }
+            }
+
+            diagnostics.append(&mut validate_expression(
+                prototypes,
+                native_functions,
+                records,
+                constants,
+                variables,
+                &lambda.body,
+            ));
+
+            for (name, shadowed) in bindings.into_iter().rev() {
+                if let Some((location, 0)) = variables.remove(name) {
+                    diagnostics.push(
+                        Diagnostic::warning(
+                            Code::UnusedVariable,
+                            format!("variable \"{}\" is never used", name),
+                        )
+                        .at(location),
+                    );
+                }
+
+                if let Some(shadowed) = shadowed {
+                    variables.insert(name, shadowed);
+                }
+            }
+
+            diagnostics
         }
         Expression::Variable(var) => {
-            if let Some((_, count)) = variables.get_mut(&var.name) {
+            if let Callee::Value((_, count)) | Callee::Function((_, _, count)) = Callee::lookup(
+                || {
+                    variables
+                        .get_mut(&var.name)
+                        .or_else(|| constants.get_mut(&var.name))
+                },
+                || prototypes.get_mut(&var.name),
+            ) {
                 *count += 1;
                 vec![]
+            } else if native_functions.contains_key(var.name.as_str()) {
+                vec![Diagnostic::error(
+                    Code::UnknownVariable,
+                    format!("unknown variable \"{}\"", var.name),
+                )
+                .at(var.location.clone())
+                .with_help(format!(
+                    "native functions cannot be used as values, wrap it in a lambda such as `|x| {}(x)`",
+                    var.name
+                ))]
             } else {
-                vec![Report {
-                    location: var.location.clone(),
-                    issue: format!("unknown variable \"{}\"", var.name),
-                }]
+                vec![Diagnostic::error(
+                    Code::UnknownVariable,
+                    format!("unknown variable \"{}\"", var.name),
+                )
+                .at(var.location.clone())]
             }
         }
         _ => Vec::new(),
     }
 }
+
+/// Reports fields named like a previous one.
+fn validate_fields<'a>(
+    fields: impl Iterator<Item = (&'a String, &'a Location)>,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut seen: HashMap<&String, &Location> = HashMap::new();
+
+    for (name, location) in fields {
+        if let Some(previous) = seen.get(name) {
+            diagnostics.push(
+                Diagnostic::error(
+                    Code::DuplicatedField,
+                    format!("several fields have the name \"{}\"", name),
+                )
+                .at(location.clone())
+                .with_label((*previous).clone(), "first defined here".to_owned()),
+            );
+        } else {
+            seen.insert(name, location);
+        }
+    }
+
+    diagnostics
+}
+
+fn validate_type_annotation(
+    annotation: &TypeAnnotation,
+    records: &[&RecordDefinition],
+) -> Option<Diagnostic> {
+    let is_record = |name: &str| records.iter().any(|record| record.name == name);
+
+    match annotation {
+        TypeAnnotation::Named(named) if Type::annotated(annotation, &is_record).is_none() => Some(
+            Diagnostic::error(
+                Code::UnknownType,
+                format!("unknown type \"{}\"", named.name),
+            )
+            .at(named.location.clone())
+            .with_help(
+                "available types are Int, Float, Bool, Unit, String, declared records and lists such as [Int]"
+                    .to_owned(),
+            ),
+        ),
+        TypeAnnotation::Named(_) => None,
+        TypeAnnotation::List(list) => validate_type_annotation(&list.element, records),
+    }
+}
+
+fn same_literal(a: &Literal, b: &Literal) -> bool {
+    match (a, b) {
+        (Literal::Bool(a), Literal::Bool(b)) => a.value == b.value,
+        (Literal::Number(a), Literal::Number(b)) => a.value == b.value,
+        (Literal::Float(a), Literal::Float(b)) => a.value == b.value,
+        (Literal::String(a), Literal::String(b)) => a.value == b.value,
+        _ => false,
+    }
+}
+
+/// The names each function and constant refers to.
+fn dependencies(ast: &AST) -> HashMap<&str, Vec<&str>> {
+    let mut dependencies: HashMap<&str, Vec<&str>> = HashMap::new();
+
+    for statement in &ast.statements {
+        match statement {
+            Statement::FunctionDefinition(definition) => {
+                let mut bound = definition
+                    .prototype
+                    .arguments
+                    .iter()
+                    .map(|argument| argument.name.as_str())
+                    .collect();
+                let mut names = Vec::new();
+                definition.body.references(&mut bound, &mut names);
+                dependencies.insert(&definition.prototype.name, names);
+            }
+            Statement::ConstantDefinition(constant) => {
+                let mut names = Vec::new();
+                constant.value.references(&mut Vec::new(), &mut names);
+                dependencies.insert(&constant.variable.name, names);
+            }
+            _ => {}
+        }
+    }
+
+    dependencies
+}
+
+/// Reports constants whose value depends on itself, directly or through the
+/// functions evaluating it calls.
+fn validate_constant_cycles(ast: &AST, dependencies: &HashMap<&str, Vec<&str>>) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut reported = Vec::new();
+
+    for statement in &ast.statements {
+        let Statement::ConstantDefinition(constant) = statement else {
+            continue;
+        };
+        let name = constant.variable.name.as_str();
+
+        if reported.contains(&name) {
+            continue;
+        }
+
+        let mut path = vec![name];
+        if depends_on(name, dependencies, &mut path, &mut Vec::new()) {
+            diagnostics.push(
+                Diagnostic::error(
+                    Code::CyclicConstant,
+                    format!(
+                        "constant \"{}\" depends on its own value: {}",
+                        name,
+                        path.join(" -> ")
+                    ),
+                )
+                .at(constant.location.clone())
+                .with_help(
+                    "constants are evaluated before `main`, each one after those it uses"
+                        .to_owned(),
+                ),
+            );
+            reported.extend(path);
+        }
+    }
+
+    diagnostics
+}
+
+/// Reports constants whose value reads input or writes output, directly or
+/// through the functions evaluating it calls.
+fn validate_constant_purity(ast: &AST, dependencies: &HashMap<&str, Vec<&str>>) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for statement in &ast.statements {
+        let Statement::ConstantDefinition(constant) = statement else {
+            continue;
+        };
+
+        for native in INPUT_OUTPUT {
+            // Functions of the program take precedence over native ones.
+            if dependencies.contains_key(native) {
+                continue;
+            }
+
+            let mut path = vec![constant.variable.name.as_str()];
+            if depends_on(native, dependencies, &mut path, &mut Vec::new()) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        Code::ImpureConstant,
+                        format!(
+                            "constant \"{}\" reads input or writes output: {}",
+                            constant.variable.name,
+                            path.join(" -> ")
+                        ),
+                    )
+                    .at(constant.location.clone())
+                    .with_help(
+                        "constants are evaluated before `main`, use a function instead".to_owned(),
+                    ),
+                );
+                break;
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// Tells whether the last name of a path leads to the given name, in
+/// which case the path is completed up to it.
+fn depends_on<'a>(
+    target: &str,
+    dependencies: &HashMap<&str, Vec<&'a str>>,
+    path: &mut Vec<&'a str>,
+    visited: &mut Vec<&'a str>,
+) -> bool {
+    let Some(names) = dependencies.get(path[path.len() - 1]) else {
+        return false;
+    };
+
+    for name in names {
+        if *name == target {
+            path.push(name);
+            return true;
+        }
+
+        if !visited.contains(name) {
+            visited.push(name);
+            path.push(name);
+
+            if depends_on(target, dependencies, path, visited) {
+                return true;
+            }
+
+            path.pop();
+        }
+    }
+
+    false
+}
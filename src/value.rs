@@ -0,0 +1,156 @@
+use std::collections::{BTreeMap, HashMap};
+
+use super::ast::{FunctionDefinition, Lambda};
+use super::diagnostics::{Code, Diagnostic};
+
+/// What expressions evaluate to at runtime. Functions refer to the program
+/// they are defined in, hence the lifetime.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value<'a> {
+    Bool(bool),
+    Number(i64),
+    Float(f64),
+    String(String),
+    List(Vec<Value<'a>>),
+    Record(BTreeMap<String, Value<'a>>),
+    Function(Function<'a>),
+}
+
+#[derive(Debug, Clone)]
+pub enum Function<'a> {
+    /// A function of the program, referred to by its name.
+    Named(&'a FunctionDefinition),
+    /// A lambda, along with the variables of the scope it was evaluated in.
+    Lambda(&'a Lambda, HashMap<&'a str, Value<'a>>),
+}
+
+impl Function<'_> {
+    pub fn arity(&self) -> usize {
+        match self {
+            Function::Named(function) => function.prototype.arguments.len(),
+            Function::Lambda(lambda, _) => lambda.arguments.len(),
+        }
+    }
+}
+
+/// Functions are equal when they run the same code over the same captured
+/// variables.
+impl PartialEq for Function<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Function::Named(a), Function::Named(b)) => std::ptr::eq(*a, *b),
+            (Function::Lambda(a, a_captured), Function::Lambda(b, b_captured)) => {
+                std::ptr::eq(*a, *b) && a_captured == b_captured
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<'a> Value<'a> {
+    pub fn boolean(&self) -> Result<bool, Diagnostic> {
+        match self {
+            Value::Bool(boolean) => Ok(*boolean),
+            _ => Err(self.mismatch("a boolean")),
+        }
+    }
+
+    pub fn number(&self) -> Result<i64, Diagnostic> {
+        match self {
+            Value::Number(number) => Ok(*number),
+            _ => Err(self.mismatch("a number")),
+        }
+    }
+
+    pub fn float(&self) -> Result<f64, Diagnostic> {
+        match self {
+            Value::Float(float) => Ok(*float),
+            _ => Err(self.mismatch("a float")),
+        }
+    }
+
+    pub fn string(&self) -> Result<&str, Diagnostic> {
+        match self {
+            Value::String(string) => Ok(string),
+            _ => Err(self.mismatch("a string")),
+        }
+    }
+
+    pub fn list(&self) -> Result<&[Value<'a>], Diagnostic> {
+        match self {
+            Value::List(list) => Ok(list),
+            _ => Err(self.mismatch("a list")),
+        }
+    }
+
+    pub fn field(&self, name: &str) -> Result<&Value<'a>, Diagnostic> {
+        match self {
+            Value::Record(fields) => fields.get(name).ok_or_else(|| {
+                Diagnostic::error(
+                    Code::UnknownField,
+                    format!("record `{}` has no field \"{}\"", self, name),
+                )
+            }),
+            _ => Err(self.mismatch("a record")),
+        }
+    }
+
+    pub fn function(&self) -> Result<&Function<'a>, Diagnostic> {
+        match self {
+            Value::Function(function) => Ok(function),
+            _ => Err(self.mismatch("a function")),
+        }
+    }
+
+    fn mismatch(&self, expected: &str) -> Diagnostic {
+        Diagnostic::error(
+            Code::TypeMismatch,
+            format!("expected {}, found `{}`", expected, self),
+        )
+    }
+}
+
+impl Value<'_> {
+    /// Displays a value held by another one. Strings are then quoted, so
+    /// their bounds show.
+    fn nested(&self) -> String {
+        match self {
+            Value::String(string) => format!("{:?}", string),
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for Value<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Value::Bool(boolean) => write!(f, "{}", boolean),
+            Value::Number(number) => write!(f, "{}", number),
+            // Unlike `Display`, `Debug` keeps the decimal point of round floats.
+            Value::Float(float) => write!(f, "{:?}", float),
+            Value::String(string) => write!(f, "{}", string),
+            Value::List(list) => write!(
+                f,
+                "[{}]",
+                list.iter()
+                    .map(Value::nested)
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            Value::Record(fields) if fields.is_empty() => write!(f, "{{}}"),
+            Value::Record(fields) => write!(
+                f,
+                "{{ {} }}",
+                fields
+                    .iter()
+                    .map(|(name, value)| format!("{}: {}", name, value.nested()))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            Value::Function(Function::Named(function)) => {
+                write!(f, "<function {}>", function.prototype.name)
+            }
+            Value::Function(Function::Lambda(..)) => write!(f, "<lambda>"),
+        }
+    }
+}
@@ -4,8 +4,20 @@ use super::graph::{Digraph, Edge, Node};
 pub fn function_calls_graph(path: &str, ast: &AST) -> Digraph {
     let mut graph = Digraph::new(path);
 
+    let functions: Vec<&str> = ast
+        .statements
+        .iter()
+        .filter_map(|statement| match statement {
+            Statement::FunctionDefinition(function) => Some(function.prototype.name.as_str()),
+            _ => None,
+        })
+        .collect();
+
     for statement in &ast.statements {
-        let Statement::FunctionDefinition(function) = statement;
+        let Statement::FunctionDefinition(function) = statement else {
+            continue;
+        };
+
         graph.nodes.push(Node {
             id: function.prototype.name.to_owned(),
             label: function.prototype.name.to_owned(),
@@ -14,6 +26,7 @@ pub fn function_calls_graph(path: &str, ast: &AST) -> Digraph {
         function_calls_extract_from_expression(
             &function.body,
             &function.prototype.name,
+            &functions,
             &mut graph,
         );
     }
@@ -24,6 +37,7 @@ pub fn function_calls_graph(path: &str, ast: &AST) -> Digraph {
 fn function_calls_extract_from_expression(
     expression: &Expression,
     parent_function: &str,
+    functions: &[&str],
     graph: &mut Digraph,
 ) {
     match expression {
@@ -33,13 +47,95 @@ fn function_calls_extract_from_expression(
                 destination: call.name.to_owned(),
             });
             for parameter in &call.parameters {
-                function_calls_extract_from_expression(parameter, parent_function, graph);
+                function_calls_extract_from_expression(
+                    parameter,
+                    parent_function,
+                    functions,
+                    graph,
+                );
+            }
+        }
+        Expression::Block(block) => {
+            for expression in &block.expressions {
+                function_calls_extract_from_expression(
+                    expression,
+                    parent_function,
+                    functions,
+                    graph,
+                );
+            }
+        }
+        Expression::Let(binding) => {
+            function_calls_extract_from_expression(
+                &binding.value,
+                parent_function,
+                functions,
+                graph,
+            );
+        }
+        Expression::List(list) => {
+            for element in &list.elements {
+                function_calls_extract_from_expression(element, parent_function, functions, graph);
+            }
+        }
+        Expression::Match(r#match) => {
+            function_calls_extract_from_expression(
+                &r#match.value,
+                parent_function,
+                functions,
+                graph,
+            );
+            for arm in &r#match.arms {
+                function_calls_extract_from_expression(
+                    &arm.body,
+                    parent_function,
+                    functions,
+                    graph,
+                );
             }
         }
+        Expression::Record(record) => {
+            for field in &record.fields {
+                function_calls_extract_from_expression(
+                    &field.value,
+                    parent_function,
+                    functions,
+                    graph,
+                );
+            }
+        }
+        Expression::FieldAccess(access) => {
+            function_calls_extract_from_expression(
+                &access.record,
+                parent_function,
+                functions,
+                graph,
+            );
+        }
         Expression::Branch(branch) => {
-            function_calls_extract_from_expression(&*branch.condition, parent_function, graph);
-            function_calls_extract_from_expression(&*branch.then, parent_function, graph);
-            function_calls_extract_from_expression(&*branch.r#else, parent_function, graph);
+            function_calls_extract_from_expression(
+                &branch.condition,
+                parent_function,
+                functions,
+                graph,
+            );
+            function_calls_extract_from_expression(&branch.then, parent_function, functions, graph);
+            function_calls_extract_from_expression(
+                &branch.r#else,
+                parent_function,
+                functions,
+                graph,
+            );
+        }
+        Expression::Lambda(lambda) => {
+            function_calls_extract_from_expression(&lambda.body, parent_function, functions, graph);
+        }
+        // Functions used as values are likely to be called.
+        Expression::Variable(variable) if functions.contains(&variable.name.as_str()) => {
+            graph.edges.push(Edge {
+                source: parent_function.to_owned(),
+                destination: variable.name.to_owned(),
+            });
         }
         _ => {}
     }
@@ -0,0 +1,28 @@
+//! Helpers shared by the integration tests, each of which only uses some of
+//! them.
+#![allow(dead_code)]
+
+use idem::ast::AST;
+use idem::diagnostics::Diagnostic;
+use idem::lexing::Lexer;
+use idem::parsing::Parser;
+
+/// Lexes and parses a source, returning the lexical and syntax errors along
+/// with the AST the parser recovered from them.
+pub fn parse_with_errors(path: &str, source: &str) -> (AST, Vec<Diagnostic>) {
+    let (tokens, mut diagnostics) = Lexer::new(path).tokenize(source.chars());
+    let mut tokens_stream = tokens.iter();
+
+    let (ast, mut syntax_errors) = Parser::new(&mut tokens_stream).parse();
+    diagnostics.append(&mut syntax_errors);
+
+    (ast, diagnostics)
+}
+
+/// Lexes and parses a source which is expected to have no error.
+pub fn parse(path: &str, source: &str) -> AST {
+    let (ast, diagnostics) = parse_with_errors(path, source);
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+
+    ast
+}
@@ -0,0 +1,62 @@
+use idem::emitting::{Emitter, MessageFormat};
+use idem::reading::Sources;
+use idem::resolving::resolve;
+use idem::validation::validate;
+
+const SOURCE: &str = "main() {\n\tprint(\"😀\" == x)\n}\n";
+
+/// Emits the diagnostics of validating a source in a format, returning the
+/// path of the source along with what was written.
+fn emit(test: &str, format: MessageFormat) -> (String, String) {
+    let directory = std::env::temp_dir().join(format!("idem-emitting-{}", test));
+    std::fs::create_dir_all(&directory).unwrap();
+
+    let path = directory.join("main.id");
+    std::fs::write(&path, SOURCE).unwrap();
+    let path = path.to_string_lossy().into_owned();
+
+    let mut sources = Sources::new();
+    let (ast, diagnostics) = resolve(&mut sources, &path);
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+
+    let output = directory.join("output");
+    let mut emitter = Emitter::new(
+        format,
+        Box::new(std::fs::File::create(&output).unwrap()),
+        false,
+    );
+    emitter.emit(&sources, &validate(&ast));
+    emitter.finish();
+    drop(emitter);
+
+    (path, std::fs::read_to_string(output).unwrap())
+}
+
+// Columns count characters, the lexer counting a tabulation as four.
+#[test]
+fn json() {
+    let (path, output) = emit("json", MessageFormat::Json);
+
+    assert_eq!(
+        output,
+        format!(
+            "{{\"severity\":\"error\",\"code\":\"E0301\",\"message\":\"unknown variable \\\"x\\\"\",\"path\":\"{}\",\"line\":2,\"column\":15,\"end_line\":2,\"end_column\":16,\"offset\":26,\"end_offset\":27,\"labels\":[],\"help\":null}}\n",
+            path
+        )
+    );
+}
+
+// Columns count UTF-16 code units, the emoji counting as two.
+#[test]
+fn sarif() {
+    let (path, output) = emit("sarif", MessageFormat::Sarif);
+
+    assert_eq!(
+        output,
+        format!(
+            "{{\"$schema\":\"https://json.schemastore.org/sarif-2.1.0.json\",\"version\":\"2.1.0\",\"runs\":[{{\"tool\":{{\"driver\":{{\"name\":\"idem\",\"version\":\"{}\"}}}},\"columnKind\":\"utf16CodeUnits\",\"results\":[{{\"ruleId\":\"E0301\",\"level\":\"error\",\"message\":{{\"text\":\"unknown variable \\\"x\\\"\"}},\"locations\":[{{\"physicalLocation\":{{\"artifactLocation\":{{\"uri\":\"{}\"}},\"region\":{{\"startLine\":2,\"startColumn\":16,\"endLine\":2,\"endColumn\":17,\"byteOffset\":26,\"byteLength\":1}}}}}}],\"relatedLocations\":[]}}]}}]}}\n",
+            env!("CARGO_PKG_VERSION"),
+            path
+        )
+    );
+}
@@ -0,0 +1,124 @@
+mod common;
+
+use idem::formatting::Formattable;
+
+fn format(source: &str) -> String {
+    common::parse("test.id", source).format()
+}
+
+fn format_expression(expression: &str) -> String {
+    let formatted = format(&format!("main() {{\n\t{}\n}}", expression));
+
+    formatted
+        .strip_prefix("main() {\n")
+        .and_then(|body| body.strip_suffix("\n}"))
+        .unwrap()
+        .lines()
+        .map(|line| line.strip_prefix('\t').unwrap())
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+#[test]
+fn operators_desugar_to_calls() {
+    assert_eq!(format_expression("add(1, 2 * 3)"), "add(1, 2 * 3)");
+    assert_eq!(format_expression("1 + 2 * 3"), "1 + 2 * 3");
+    assert_eq!(format_expression("1+2*3"), "1 + 2 * 3");
+}
+
+#[test]
+fn minimal_parentheses() {
+    assert_eq!(format_expression("((1 + 2)) * (3)"), "(1 + 2) * 3");
+    assert_eq!(format_expression("(5 - 2) + 1"), "5 - 2 + 1");
+    assert_eq!(format_expression("5 - (2 + 1)"), "5 - (2 + 1)");
+    assert_eq!(format_expression("a || (b && c)"), "a || b && c");
+    assert_eq!(format_expression("(a || b) && !c"), "(a || b) && !c");
+    assert_eq!(format_expression("-(a + b) * -2"), "-(a + b) * -2");
+    assert_eq!(
+        format_expression("a <= b == (c != d)"),
+        "a <= b == (c != d)"
+    );
+}
+
+#[test]
+fn operators_on_a_new_line_start_an_expression() {
+    assert_eq!(format_expression("a\n\t-b"), "a\n-b");
+    assert_eq!(format_expression("a -\n\tb"), "a - b");
+}
+
+#[test]
+fn literals_round_trip() {
+    for literal in [
+        "-9223372036854775808",
+        "1.50",
+        "2e10",
+        "2e-3",
+        "\"a\\\"b\\n\"",
+    ] {
+        assert_eq!(format_expression(literal), literal);
+    }
+}
+
+#[test]
+fn flat_else_if_chains() {
+    let source =
+        "sign(n) {\n\tif n < 0 {\n\t\t-1\n\t} else if n == 0 {\n\t\t0\n\t} else {\n\t\t1\n\t}\n}";
+    assert_eq!(format(source), source);
+}
+
+#[test]
+fn match_arms() {
+    assert_eq!(
+        format("f(n) {\n\tmatch n { 0 => \"zero\", -1 => { \"minus one\" } _ => n }\n}"),
+        "f(n) {\n\tmatch n {\n\t\t0 => \"zero\"\n\t\t-1 => {\n\t\t\t\"minus one\"\n\t\t}\n\t\t_ => n\n\t}\n}"
+    );
+}
+
+#[test]
+fn record_arm_bodies() {
+    let formatted = format("f(n) {\n\tmatch n { 1 => ({ x: 2 }), _ => { { x: n } } }\n}");
+    assert_eq!(
+        formatted,
+        "f(n) {\n\tmatch n {\n\t\t1 => ({ x: 2 })\n\t\t_ => {\n\t\t\t{ x: n }\n\t\t}\n\t}\n}"
+    );
+    assert_eq!(format(&formatted), formatted);
+}
+
+#[test]
+fn records() {
+    assert_eq!(
+        format("record Point { x: Int, y: [Float] }"),
+        "record Point {\n\tx: Int\n\ty: [Float]\n}"
+    );
+    assert_eq!(
+        format_expression("{x:1,y:{ z: 2 }}.y.z"),
+        "{ x: 1, y: { z: 2 } }.y.z"
+    );
+}
+
+#[test]
+fn lambdas() {
+    assert_eq!(
+        format_expression("map(list,|n:Int|n*2)"),
+        "map(list, |n: Int| n * 2)"
+    );
+    assert_eq!(format_expression("(|| 1) == f"), "(|| 1) == f");
+    assert_eq!(
+        format_expression("let f = |a, b| {\n\tprint(a)\n\tb\n}"),
+        "let f = |a, b| {\n\tprint(a)\n\tb\n}"
+    );
+    let source = "main() {\n\tlet f = |a| ({ x: a })\n}";
+    assert_eq!(format(source), source);
+}
+
+#[test]
+fn imports() {
+    let source = "import \"lib/math.id\"\nimport \"shapes.id\"\n\nmain() {\n\tprint(math.square(shapes.side))\n}";
+    assert_eq!(format(source), source);
+}
+
+#[test]
+fn constants() {
+    let source = "const LIMIT: Int = 20\nconst HALF = LIMIT / 2\n\nmain() {\n\tprint(HALF)\n}";
+    assert_eq!(format(source), source);
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use idem::diagnostics::{Code, Diagnostic, Severity};
+use idem::interpretation::Interpreter;
+
+/// Runs a program, returning its exit status along with what it wrote.
+fn execute(source: &str, arguments: &[&str], input: &str) -> (Result<i32, Diagnostic>, String) {
+    let ast = common::parse("test.id", source);
+
+    let arguments: Vec<String> = arguments.iter().map(|arg| arg.to_string()).collect();
+    let mut interpreter = Interpreter::new(input.as_bytes(), Vec::new());
+    let status = interpreter.run(&ast, &arguments);
+
+    (
+        status,
+        String::from_utf8(interpreter.output().clone()).unwrap(),
+    )
+}
+
+fn interpret(source: &str, arguments: &[&str], input: &str) -> (Result<i32, Code>, String) {
+    let (status, output) = execute(source, arguments, input);
+    (status.map_err(|diagnostic| diagnostic.code), output)
+}
+
+fn run_source(source: &str, arguments: &[&str], input: &str) -> Result<i32, Code> {
+    interpret(source, arguments, input).0
+}
+
+#[test]
+fn main_arguments_and_exit_status() {
+    assert_eq!(run_source("main() {\n\tlet a = 1\n}\n", &[], ""), Ok(0));
+
+    let source = "main(n: Int, offset, negative: Bool, name: String) {\n\tif negative && name == \"idem\" {\n\t\toffset - n\n\t} else {\n\t\tn + offset\n\t}\n}\n";
+    assert_eq!(run_source(source, &["2", "3", "true", "idem"], ""), Ok(1));
+    assert_eq!(run_source(source, &["2", "3", "false", "idem"], ""), Ok(5));
+    assert_eq!(
+        run_source(source, &["two", "3", "true", "idem"], ""),
+        Err(Code::InvalidConversion)
+    );
+    assert_eq!(run_source(source, &["2"], ""), Err(Code::ArgumentsCount));
+
+    // Statuses which Unix would truncate to another one are rejected.
+    assert_eq!(
+        run_source(source, &["200", "56", "false", "idem"], ""),
+        Err(Code::InvalidConversion)
+    );
+    assert_eq!(
+        run_source(source, &["5", "3", "true", "idem"], ""),
+        Err(Code::InvalidConversion)
+    );
+
+    assert_eq!(
+        run_source("main(n) {\n\tn * 1000000000\n}\n", &["10"], ""),
+        Err(Code::InvalidConversion)
+    );
+}
+
+#[test]
+fn reading_input() {
+    let source = "sum(total: Int): Int {\n\tif end_of_input() {\n\t\ttotal\n\t} else {\n\t\tsum(total + read_number())\n\t}\n}\n\nmain() {\n\tif read_line() == \"sum\" {\n\t\tsum(0)\n\t} else {\n\t\t0\n\t}\n}\n";
+    assert_eq!(run_source(source, &[], "sum\n1\n 2 \r\n3"), Ok(6));
+    assert_eq!(
+        run_source(source, &[], "sum\n1\ntwo\n"),
+        Err(Code::InvalidConversion)
+    );
+    assert_eq!(run_source(source, &[], "product\n1\n"), Ok(0));
+
+    assert_eq!(
+        run_source("main() {\n\tread_line()\n\tread_number()\n}\n", &[], "1\n"),
+        Err(Code::EndOfInput)
+    );
+}
+
+#[test]
+fn program_output() {
+    let source = "main() {\n\tprint(read_line())\n\tprint([1, 2])\n\tprint(1 / 0)\n}\n";
+    assert_eq!(
+        interpret(source, &[], "hello\n"),
+        (Err(Code::DivisionByZero), "hello\n[1, 2]\n".to_owned())
+    );
+}
+
+#[test]
+fn logical_operators_short_circuit() {
+    let source = "has_one(l, i) {\n\ti < length(l) && get(l, i) == 1\n}\n\nmain() {\n\tif !has_one([1], 1) && (true || get([], 0) == 1) {\n\t\t3\n\t} else {\n\t\t0\n\t}\n}\n";
+    assert_eq!(run_source(source, &[], ""), Ok(3));
+}
+
+#[test]
+fn runtime_errors_are_diagnostics() {
+    let source = "nothing() {\n}\n\nmain() {\n\tprint(1)\n\tadd(1, nothing())\n}\n";
+    let (status, output) = execute(source, &[], "");
+    let diagnostic = status.unwrap_err();
+
+    assert_eq!(output, "1\n");
+    assert_eq!(diagnostic.severity, Severity::Error);
+    assert_eq!(diagnostic.code, Code::MissingValue);
+    assert_eq!(diagnostic.message, "expression has no value");
+
+    let location = diagnostic.location.as_ref().unwrap();
+    assert_eq!((location.start.line, location.start.column), (6, 12));
+
+    let (status, _) = execute("square(a) {\n\tmultiply(a, a)\n}\n", &[], "");
+    let diagnostic = status.unwrap_err();
+    assert_eq!(diagnostic.code, Code::MissingMain);
+    assert!(diagnostic.location.is_none());
+
+    let (status, _) = execute("main() {\n\tprint(add(1))\n}\n", &[], "");
+    let diagnostic = status.unwrap_err();
+    assert_eq!(diagnostic.code, Code::ArgumentsCount);
+    assert_eq!(
+        diagnostic.message,
+        "function \"add\" accepts 2 arguments but received 1 parameters"
+    );
+    assert_eq!(diagnostic.location.as_ref().unwrap().start.line, 2);
+}
@@ -0,0 +1,49 @@
+use idem::diagnostics::Code;
+use idem::lexing::{Lexer, Token};
+
+#[test]
+fn string_escapes() {
+    let source = r#"print("a\tb \"c\" \\ d\n")"#;
+    let (tokens, diagnostics) = Lexer::new("test.id").tokenize(source.chars());
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+
+    let Token::String(string) = &tokens[2].token else {
+        panic!("expected a string");
+    };
+    assert_eq!(string, "a\tb \"c\" \\ d\n");
+    assert_eq!(
+        &source[tokens[2].location.start.offset..tokens[2].location.end.offset],
+        r#""a\tb \"c\" \\ d\n""#
+    );
+}
+
+#[test]
+fn invalid_strings() {
+    let (tokens, diagnostics) = Lexer::new("test.id").tokenize(r#"print("\q") "open"#.chars());
+
+    assert_eq!(tokens.len(), 4);
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[0].code, Code::InvalidEscape);
+    assert_eq!(diagnostics[1].code, Code::UnterminatedString);
+}
+
+#[test]
+fn escaped_line_breaks() {
+    let (_, diagnostics) =
+        Lexer::new("test.id").tokenize("main() {\n\tlet s = \"a\\\n\tprint(\"\\q\")\n}\n".chars());
+
+    let locations: Vec<(Code, usize, usize)> = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let location = diagnostic.location.as_ref().unwrap();
+            (diagnostic.code, location.start.line, location.start.column)
+        })
+        .collect();
+    assert_eq!(
+        locations,
+        [
+            (Code::UnterminatedString, 2, 13),
+            (Code::InvalidEscape, 3, 12)
+        ]
+    );
+}
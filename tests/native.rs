@@ -0,0 +1,74 @@
+use idem::diagnostics::Code;
+use idem::native::{
+    add, divide, divide_floats, filter, fold, greater, greater_equal, less, less_equal, map,
+    modulo, multiply, sub,
+};
+use idem::value::Value;
+
+#[test]
+fn signed_arithmetic() {
+    assert_eq!(sub(0, 1).unwrap(), -1);
+    assert_eq!(add(-5, 2).unwrap(), -3);
+    assert_eq!(multiply(-4, 3).unwrap(), -12);
+}
+
+#[test]
+fn overflow_is_an_error() {
+    assert_eq!(add(i64::MAX, 1).unwrap_err().code, Code::IntegerOverflow);
+    assert_eq!(sub(i64::MIN, 1).unwrap_err().code, Code::IntegerOverflow);
+    assert_eq!(
+        multiply(i64::MAX, 2).unwrap_err().code,
+        Code::IntegerOverflow
+    );
+}
+
+#[test]
+fn division() {
+    assert_eq!(divide(-7, 2).unwrap(), -3);
+    assert_eq!(modulo(-7, 2).unwrap(), -1);
+    assert_eq!(divide(1, 0).unwrap_err().code, Code::DivisionByZero);
+    assert_eq!(modulo(1, 0).unwrap_err().code, Code::DivisionByZero);
+    assert_eq!(
+        divide(i64::MIN, -1).unwrap_err().code,
+        Code::IntegerOverflow
+    );
+    let overflow = modulo(i64::MIN, -1).unwrap_err();
+    assert_eq!(overflow.code, Code::IntegerOverflow);
+    assert_eq!(
+        overflow.message,
+        format!("attempt to modulo {} and -1 with overflow", i64::MIN)
+    );
+    assert_eq!(
+        divide_floats(1.0, 0.0).unwrap_err().code,
+        Code::DivisionByZero
+    );
+}
+
+#[test]
+fn comparisons() {
+    assert!(less(1, 2));
+    assert!(!greater(1, 2));
+    assert!(less_equal(2.0, 2.0));
+    assert!(!greater_equal(1.5, 2.5));
+}
+
+#[test]
+fn higher_order_functions() {
+    let list = [Value::Number(1), Value::Number(2), Value::Number(3)];
+
+    assert_eq!(
+        map(&list, |n| Ok(Value::Number(n.number()? * 10))).unwrap(),
+        [Value::Number(10), Value::Number(20), Value::Number(30)]
+    );
+    assert_eq!(
+        filter(&list, |n| Ok(n.number()? != 2)).unwrap(),
+        [Value::Number(1), Value::Number(3)]
+    );
+    assert_eq!(
+        fold(&list, Value::Number(0), |sum, n| Ok(Value::Number(
+            sum.number()? + n.number()?
+        )))
+        .unwrap(),
+        Value::Number(6)
+    );
+}
@@ -0,0 +1,234 @@
+mod common;
+
+use idem::ast::{Expression, Literal, Statement};
+use idem::diagnostics::Code;
+use idem::formatting::Formattable;
+
+fn assert_unexpected_end_of_file(source: &str, expected: &str) {
+    let (_, diagnostics) = common::parse_with_errors("test.id", source);
+
+    assert_eq!(diagnostics.len(), 1, "{:?}: {:?}", source, diagnostics);
+    assert_eq!(diagnostics[0].code, Code::UnexpectedEndOfFile);
+    assert_eq!(
+        diagnostics[0].message,
+        format!("unexpected end of file, expected {}", expected)
+    );
+}
+
+#[test]
+fn truncated_prototype() {
+    assert_unexpected_end_of_file("main", "an opening parenthesis");
+    assert_unexpected_end_of_file("main(", "a closing parenthesis or a word");
+    assert_unexpected_end_of_file("foo(a", "a closing parenthesis or a word");
+    assert_unexpected_end_of_file("foo(a,", "a closing parenthesis or a word");
+    assert_unexpected_end_of_file("main()", "an opening brace");
+}
+
+#[test]
+fn truncated_body() {
+    assert_unexpected_end_of_file("main() {", "an expression");
+    assert_unexpected_end_of_file("main() { n", "a closing brace");
+    assert_unexpected_end_of_file("main() { print(", "a closing parenthesis or an expression");
+    assert_unexpected_end_of_file("main() { print(1", "a closing parenthesis or an expression");
+    assert_unexpected_end_of_file(
+        "main() { print(1,",
+        "a closing parenthesis or an expression",
+    );
+    assert_unexpected_end_of_file("main() { print(1)", "a closing brace");
+}
+
+#[test]
+fn truncated_branch() {
+    assert_unexpected_end_of_file("main() { if", "an expression");
+    assert_unexpected_end_of_file("main() { if n", "an opening brace");
+    assert_unexpected_end_of_file("main() { if n {", "an expression");
+    assert_unexpected_end_of_file("main() { if n { 1", "a closing brace");
+    assert_unexpected_end_of_file("main() { if n { 1 } else", "an opening brace");
+    assert_unexpected_end_of_file("main() { if n { 1 } else {", "an expression");
+    assert_unexpected_end_of_file("main() { if n { 1 } else { 2 }", "a closing brace");
+}
+
+#[test]
+fn end_of_file_points_at_last_token() {
+    let (_, diagnostics) = common::parse_with_errors("test.id", "main() {\n\tprint(1,");
+    let location = diagnostics[0].location.as_ref().unwrap();
+
+    assert_eq!((location.start.line, location.start.column), (2, 12));
+}
+
+#[test]
+fn complete_program() {
+    let (_, diagnostics) = common::parse_with_errors("test.id", "main() {\n\tprint(1)\n}\n");
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+}
+
+#[test]
+fn block_separators() {
+    let (_, diagnostics) = common::parse_with_errors(
+        "test.id",
+        "main() {\n\tprint(1)\n\tprint(2); print(3);\n}\n",
+    );
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+
+    let (_, diagnostics) =
+        common::parse_with_errors("test.id", "main() {\n\tprint(1) print(2)\n}\n");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].message,
+        "expected a semicolon, a new line or a closing brace"
+    );
+}
+
+#[test]
+fn spans_cover_whole_nodes() {
+    let source = "main() {\n\tprint(add(1, n))\n}\n";
+    let (ast, _) = common::parse_with_errors("test.id", source);
+
+    let Statement::FunctionDefinition(main) = &ast.statements[0] else {
+        panic!("expected a function definition");
+    };
+    assert_eq!(
+        &source[main.location.start.offset..main.location.end.offset],
+        source.trim_end()
+    );
+    assert_eq!(
+        &source[main.prototype.location.start.offset..main.prototype.location.end.offset],
+        "main()"
+    );
+
+    let Expression::Block(body) = &main.body else {
+        panic!("expected a block");
+    };
+    let Expression::FunctionCall(print) = &body.expressions[0] else {
+        panic!("expected a function call");
+    };
+    assert_eq!(
+        &source[print.location.start.offset..print.location.end.offset],
+        "print(add(1, n))"
+    );
+    assert_eq!(
+        (print.location.start.line, print.location.start.column),
+        (2, 5)
+    );
+    assert_eq!(
+        (print.location.end.line, print.location.end.column),
+        (2, 21)
+    );
+
+    let Expression::FunctionCall(add) = &print.parameters[0] else {
+        panic!("expected a function call");
+    };
+    for (parameter, text) in add.parameters.iter().zip(["1", "n"]) {
+        let location = parameter.location().unwrap();
+        assert_eq!(&source[location.start.offset..location.end.offset], text);
+    }
+}
+
+#[test]
+fn type_annotations() {
+    let source = "square(a: Int): Int {\n\tlet b: Int = a\n\tmultiply(b, b)\n}\n";
+    let (ast, diagnostics) = common::parse_with_errors("test.id", source);
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+
+    let Statement::FunctionDefinition(square) = &ast.statements[0] else {
+        panic!("expected a function definition");
+    };
+    assert_eq!(
+        square.prototype.arguments[0]
+            .r#type
+            .as_ref()
+            .unwrap()
+            .format(),
+        "Int"
+    );
+    assert_eq!(
+        square.prototype.return_type.as_ref().unwrap().format(),
+        "Int"
+    );
+
+    assert_unexpected_end_of_file("square(a:", "a type");
+    assert_unexpected_end_of_file("square():", "a type");
+}
+
+#[test]
+fn lists() {
+    let (_, diagnostics) = common::parse_with_errors(
+        "test.id",
+        "sum(l: [[Int]]) {\n\tprint([1, [2, 3], []])\n}\n",
+    );
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+
+    assert_unexpected_end_of_file("main() { [1,", "a closing bracket or an expression");
+    assert_unexpected_end_of_file("sum(l: [Int", "a closing bracket");
+}
+
+#[test]
+fn number_literals() {
+    let source = "main() {\n\tprint(1, -2, 1.5, 2e10, -0.5, inf)\n}\n";
+    let (ast, diagnostics) = common::parse_with_errors("test.id", source);
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+
+    let Statement::FunctionDefinition(main) = &ast.statements[0] else {
+        panic!("expected a function definition");
+    };
+    let Expression::Block(body) = &main.body else {
+        panic!("expected a block");
+    };
+    let Expression::FunctionCall(print) = &body.expressions[0] else {
+        panic!("expected a function call");
+    };
+
+    let kinds = print
+        .parameters
+        .iter()
+        .map(|parameter| match parameter {
+            Expression::Literal(Literal::Number(number)) => format!("int {}", number.value),
+            Expression::Literal(Literal::Float(float)) => format!("float {}", float.raw),
+            Expression::Variable(variable) => format!("variable {}", variable.name),
+            _ => panic!("unexpected expression"),
+        })
+        .collect::<Vec<String>>();
+    assert_eq!(
+        kinds,
+        [
+            "int 1",
+            "int -2",
+            "float 1.5",
+            "float 2e10",
+            "float -0.5",
+            "variable inf"
+        ]
+    );
+
+    let (_, diagnostics) = common::parse_with_errors(
+        "test.id",
+        "main() {\n\tprint(99999999999999999999, 1e999)\n}\n",
+    );
+    assert_eq!(diagnostics.len(), 2);
+    assert!(diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.code == Code::NumberOutOfRange));
+}
+
+#[test]
+fn recovers_from_syntax_errors() {
+    let source =
+        "first() {\n\tprint(1 +)\n}\n\nsecond() {\n\tlet = 3\n}\n\nmain() {\n\tprint(3)\n}\n";
+    let (ast, diagnostics) = common::parse_with_errors("test.id", source);
+
+    let lines = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.location.as_ref().unwrap().start.line)
+        .collect::<Vec<usize>>();
+    assert_eq!(lines, [2, 6], "{:?}", diagnostics);
+
+    let names = ast
+        .statements
+        .iter()
+        .filter_map(|statement| match statement {
+            Statement::FunctionDefinition(function) => Some(function.prototype.name.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<&str>>();
+    assert_eq!(names, ["first", "second", "main"]);
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use idem::reading::Sources;
+use idem::rendering::Renderer;
+use idem::validation::validate;
+
+/// Renders the diagnostics of validating a source without colors, returning
+/// the path of the source along with what was rendered.
+fn render(test: &str, source: &str) -> (String, Vec<String>) {
+    let directory = std::env::temp_dir().join(format!("idem-rendering-{}", test));
+    std::fs::create_dir_all(&directory).unwrap();
+
+    let path = directory.join("main.id");
+    std::fs::write(&path, source).unwrap();
+    let path = path.to_string_lossy().into_owned();
+
+    let mut sources = Sources::new();
+    let source = sources.read(&path).unwrap();
+    let ast = common::parse(&path, source);
+
+    let renderer = Renderer::new(&sources, false);
+    let rendered = validate(&ast)
+        .iter()
+        .map(|diagnostic| renderer.render(diagnostic))
+        .collect();
+
+    (path, rendered)
+}
+
+#[test]
+fn labels() {
+    let (path, rendered) = render(
+        "labels",
+        "main() {\n\tprint(square(1, 2))\n}\n\nsquare(a) {\n\tmultiply(a, a)\n}\n",
+    );
+
+    assert_eq!(
+        rendered,
+        [format!(
+            "error[E0302]: function \"square\" accepts 1 arguments but received 2 parameters\n --> {}:2:11\n  |\n2 |     print(square(1, 2))\n  |           ^^^^^^^^^^^^\n  |\n5 | square(a) {{\n  | --------- function defined here",
+            path
+        )]
+    );
+}
+
+// Spans over more than two lines elide the lines in between.
+#[test]
+fn long_spans() {
+    let (path, rendered) = render(
+        "long-spans",
+        "main() {\n\tprint(square(\n\t\t1,\n\t\t2,\n\t\t3\n\t))\n}\n\nsquare(a) {\n\tmultiply(a, a)\n}\n",
+    );
+
+    assert_eq!(
+        rendered,
+        [format!(
+            "error[E0302]: function \"square\" accepts 1 arguments but received 3 parameters\n --> {}:2:11\n  |\n2 |     print(square(\n  |           ^^^^^^^\n3 |         1,\n  |         ^^\n...\n6 |     ))\n  |     ^\n  |\n9 | square(a) {{\n  | --------- function defined here",
+            path
+        )]
+    );
+}
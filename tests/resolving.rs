@@ -0,0 +1,134 @@
+use std::path::PathBuf;
+
+use idem::ast::{Expression, Statement};
+use idem::diagnostics::Code;
+use idem::reading::Sources;
+use idem::resolving::resolve;
+
+/// Writes files to a directory of their own, returning its path.
+fn write_modules(test: &str, files: &[(&str, &str)]) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("idem-resolving-{}", test));
+    std::fs::create_dir_all(&directory).unwrap();
+
+    for (name, content) in files {
+        std::fs::write(directory.join(name), content).unwrap();
+    }
+
+    directory
+}
+
+fn function_names(statements: &[Statement]) -> Vec<&str> {
+    statements
+        .iter()
+        .filter_map(|statement| match statement {
+            Statement::FunctionDefinition(function) => Some(function.prototype.name.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn imported_names_are_qualified() {
+    let directory = write_modules(
+        "qualified",
+        &[
+            ("math.id", "square(n) {\n\tn * n\n}\n\ncube(n) {\n\tsquare(n) * n\n}\n"),
+            ("shapes.id", "import \"math.id\"\n\narea(side) {\n\tmath.square(side)\n}\n"),
+            (
+                "main.id",
+                "import \"math.id\"\nimport \"shapes.id\"\n\nmain() {\n\tprint(shapes.area(math.cube(2)))\n}\n",
+            ),
+        ],
+    );
+
+    let mut sources = Sources::new();
+    let (ast, diagnostics) = resolve(&mut sources, directory.join("main.id").to_str().unwrap());
+
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    // `math.id` is imported twice but only loaded once.
+    assert_eq!(
+        function_names(&ast.statements),
+        ["math.square", "math.cube", "shapes.area", "main"]
+    );
+
+    let Statement::FunctionDefinition(cube) = &ast.statements[1] else {
+        panic!("expected a function definition");
+    };
+    let Expression::Block(body) = &cube.body else {
+        panic!("expected a block");
+    };
+    let Expression::FunctionCall(call) = &body.expressions[0] else {
+        panic!("expected a call");
+    };
+    assert_eq!(call.parameters.len(), 2);
+    let Expression::FunctionCall(square) = &call.parameters[0] else {
+        panic!("expected a call");
+    };
+    assert_eq!(square.name, "math.square");
+}
+
+#[test]
+fn import_cycles() {
+    let directory = write_modules(
+        "cycle",
+        &[
+            ("a.id", "import \"b.id\"\n\nmain() {\n\tprint(1)\n}\n"),
+            ("b.id", "import \"a.id\"\n"),
+        ],
+    );
+
+    let mut sources = Sources::new();
+    let (_, diagnostics) = resolve(&mut sources, directory.join("a.id").to_str().unwrap());
+
+    let codes: Vec<Code> = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.code)
+        .collect();
+    assert_eq!(codes, [Code::ImportCycle]);
+}
+
+#[test]
+fn qualified_names_need_imports() {
+    let directory = write_modules(
+        "unknown",
+        &[("main.id", "main() {\n\tprint(math.square(2))\n}\n")],
+    );
+
+    let mut sources = Sources::new();
+    let (_, diagnostics) = resolve(&mut sources, directory.join("main.id").to_str().unwrap());
+
+    let codes: Vec<Code> = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.code)
+        .collect();
+    assert_eq!(codes, [Code::UnknownModule]);
+}
+
+#[test]
+fn lexical_errors_do_not_hide_syntax_errors() {
+    let directory = write_modules(
+        "lexical",
+        &[(
+            "main.id",
+            "main() {\n\tprint(\"\\q\")\n}\n\nsecond() {\n\tprint(1 +)\n}\n",
+        )],
+    );
+
+    let mut sources = Sources::new();
+    let (ast, diagnostics) = resolve(&mut sources, directory.join("main.id").to_str().unwrap());
+
+    let codes: Vec<(Code, usize)> = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            (
+                diagnostic.code,
+                diagnostic.location.as_ref().unwrap().start.line,
+            )
+        })
+        .collect();
+    assert_eq!(
+        codes,
+        [(Code::InvalidEscape, 2), (Code::UnexpectedToken, 6)]
+    );
+    assert_eq!(function_names(&ast.statements), ["main", "second"]);
+}
@@ -0,0 +1,155 @@
+mod common;
+
+use idem::diagnostics::{Code, Diagnostic};
+use idem::typing::check;
+
+fn check_source(source: &str) -> Vec<Diagnostic> {
+    check(&common::parse("test.id", source))
+}
+
+#[test]
+fn conditions_are_booleans() {
+    assert!(check_source("main() {\n\tif true && 1 < 2 {\n\t\tprint(1)\n\t}\n}\n").is_empty());
+
+    let diagnostics = check_source("main() {\n\tif 1 {\n\t\tprint(1)\n\t}\n}\n");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, Code::NonBooleanCondition);
+}
+
+#[test]
+fn functions_as_values() {
+    assert!(check_source(
+        "apply(f, x: Int): Int {\n\tf(x)\n}\n\nmain() {\n\tlet offset = 1\n\tprint(apply(|n| n + offset, 2))\n\tprint(fold([1, 2], 0.5, |sum, n| sum + to_float(n)))\n}\n"
+    )
+    .is_empty());
+
+    let codes: Vec<Code> = check_source(
+        "main() {\n\tlet f = |n| n + 1\n\tprint(f(1, 2))\n\tprint(map([1], |n| n == \"a\"))\n}\n",
+    )
+    .iter()
+    .map(|diagnostic| diagnostic.code)
+    .collect();
+    assert_eq!(codes, [Code::TypeMismatch, Code::TypeMismatch]);
+}
+
+#[test]
+fn generic_functions() {
+    assert!(check_source(
+        "square(n) {\n\tn * n\n}\n\nsize(x) {\n\tlength(x)\n}\n\nmain() {\n\tprint(square(1.5) + to_float(square(2)))\n\tprint(size([1]) + size(\"ab\"))\n}\n"
+    )
+    .is_empty());
+
+    let diagnostics =
+        check_source("square(n) {\n\tn * n\n}\n\nmain() {\n\tprint(square(\"a\"))\n}\n");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, Code::NoMatchingSignature);
+    assert_eq!(diagnostics[0].location.as_ref().unwrap().start.line, 6);
+}
+
+#[test]
+fn lambdas_take_parameter_types() {
+    assert!(check_source(
+        "main() {\n\tprint(map([1.5, 2.0], |x| x * x))\n\tprint(filter([1.5], |x| x > 1.0))\n\tprint(fold([1.5, 2.5], 0.0, |a, b| a + b))\n}\n"
+    )
+    .is_empty());
+
+    let diagnostics = check_source("main() {\n\tprint(map([1.5, 2.0], |x| x * 2))\n}\n");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, Code::NoMatchingSignature);
+}
+
+#[test]
+fn mutually_recursive_functions() {
+    assert!(check_source(
+        "is_even(n) {\n\tif n == 0 {\n\t\ttrue\n\t} else {\n\t\tis_odd(n - 1)\n\t}\n}\n\nis_odd(n) {\n\tif n == 0 {\n\t\tfalse\n\t} else {\n\t\tis_even(n - 1)\n\t}\n}\n\nmain() {\n\tif is_odd(3) {\n\t\tprint(1)\n\t}\n}\n"
+    )
+    .is_empty());
+
+    let diagnostics = check_source(
+        "is_even(n) {\n\tif n == 0 {\n\t\ttrue\n\t} else {\n\t\tis_odd(n - 1)\n\t}\n}\n\nis_odd(n) {\n\tif n == 0 {\n\t\tfalse\n\t} else {\n\t\tis_even(n - 1)\n\t}\n}\n\nmain() {\n\tprint(is_even(4) + 1)\n}\n",
+    );
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, Code::NoMatchingSignature);
+    assert_eq!(diagnostics[0].location.as_ref().unwrap().start.line, 18);
+}
+
+#[test]
+fn polymorphic_functions() {
+    assert!(check_source(
+        "identity(x) {\n\tx\n}\n\nfirst(list) {\n\tget(list, 0)\n}\n\nmain() {\n\tprint(identity(1) + 1)\n\tif identity(true) {\n\t\tprint(first([\"a\"]))\n\t}\n\tprint(first([1.5]) * 2.0)\n}\n"
+    )
+    .is_empty());
+
+    let diagnostics = check_source(
+        "identity(x) {\n\tx\n}\n\nmain() {\n\tif identity(1) {\n\t\tprint(1)\n\t}\n}\n",
+    );
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, Code::NonBooleanCondition);
+}
+
+#[test]
+fn parameters_are_values() {
+    let diagnostics = check_source("main() {\n\tprint(print(1))\n}\n");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, Code::TypeMismatch);
+
+    let diagnostics =
+        check_source("id(x) {\n\tx\n}\n\nmain() {\n\tid(print(1))\n\tmap([1], |n| print(n))\n}\n");
+    assert_eq!(diagnostics.len(), 2);
+}
+
+#[test]
+fn unknown_fields() {
+    let record = "record Point { x: Int, y: Int }\n\n";
+
+    for source in [
+        format!("{}main() {{\n\tprint({{ x: 1, y: 2 }}.z)\n}}\n", record),
+        format!(
+            "{}show(p) {{\n\tprint(p.z)\n}}\n\nmain() {{\n\tshow(1)\n}}\n",
+            record
+        ),
+    ] {
+        let diagnostics = check_source(&source);
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        assert_eq!(diagnostics[0].code, Code::UnknownField);
+    }
+}
+
+#[test]
+fn arguments_count_is_left_to_validation() {
+    assert!(check_source("main() {\n\tprint(add(1))\n\tprint(length())\n}\n").is_empty());
+}
+
+#[test]
+fn type_mismatches() {
+    let messages = |source| {
+        check_source(source)
+            .iter()
+            .map(|diagnostic| {
+                assert_eq!(diagnostic.code, Code::TypeMismatch);
+                let location = diagnostic.location.as_ref().unwrap();
+                (
+                    location.start.line,
+                    location.start.column,
+                    diagnostic.message.clone(),
+                )
+            })
+            .collect::<Vec<(usize, usize, String)>>()
+    };
+
+    assert!(messages("main() {\n\tprint(add(1, 2))\n}\n").is_empty());
+    assert_eq!(
+        messages("main() {\n\tprint(not(1))\n}\n"),
+        [(2, 15, "expected `Bool`, found `Int`".to_owned())]
+    );
+    assert_eq!(
+        messages(
+            "main() {\n\tprint(if equal(1, 1) {\n\t\t1\n\t} else {\n\t\tequal(1, 2)\n\t})\n}\n"
+        ),
+        [(
+            5,
+            9,
+            "branches have different types: `Int` and `Bool`".to_owned()
+        )]
+    );
+}
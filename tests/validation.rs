@@ -0,0 +1,122 @@
+mod common;
+
+use idem::diagnostics::{Code, Diagnostic};
+use idem::validation::validate;
+
+fn validate_source(source: &str) -> Vec<Diagnostic> {
+    validate(&common::parse("test.id", source))
+}
+
+fn codes(source: &str) -> Vec<Code> {
+    validate_source(source)
+        .iter()
+        .map(|diagnostic| diagnostic.code)
+        .collect()
+}
+
+#[test]
+fn match_exhaustiveness() {
+    assert!(codes("main() {\n\tprint(match 1 { 0 => 1, _ => 2 })\n}\n").is_empty());
+    assert!(codes("main() {\n\tprint(match true { true => 1, false => 2 })\n}\n").is_empty());
+
+    assert_eq!(
+        codes("main() {\n\tprint(match 1 { 0 => 1, 1 => 2 })\n}\n"),
+        [Code::NonExhaustiveMatch]
+    );
+    assert_eq!(
+        codes("main() {\n\tprint(match true { true => 1 })\n}\n"),
+        [Code::NonExhaustiveMatch]
+    );
+}
+
+#[test]
+fn unreachable_arms() {
+    assert_eq!(
+        codes("main() {\n\tprint(match 1 { 0 => 1, 0 => 2, _ => 3, 4 => 4, _ => 5 })\n}\n"),
+        [
+            Code::UnreachablePattern,
+            Code::UnreachablePattern,
+            Code::UnreachablePattern
+        ]
+    );
+}
+
+#[test]
+fn record_fields() {
+    let record = "record Point { x: Int, y: Int }\n\n";
+
+    assert!(codes(&format!(
+        "{}main() {{\n\tprint({{ y: 1, x: 2 }}.x)\n}}\n",
+        record
+    ))
+    .is_empty());
+
+    assert_eq!(
+        codes(&format!("{}main() {{\n\tprint({{ x: 1 }})\n}}\n", record)),
+        [Code::UnknownRecord]
+    );
+    let ambiguous = format!(
+        "{}record Vector {{ x: Int, y: Int }}\n\nmain() {{\n\tprint({{ x: 1, y: 2 }})\n}}\n",
+        record
+    );
+    assert_eq!(codes(&ambiguous), [Code::AmbiguousRecord]);
+    assert_eq!(
+        validate_source(&ambiguous)[0].message,
+        "records \"Point\", \"Vector\" all have the fields \"x\", \"y\""
+    );
+    assert_eq!(
+        codes("record Point { x: Int, x: Int }\n\nmain() {\n\tprint(1)\n}\n"),
+        [Code::DuplicatedField]
+    );
+}
+
+#[test]
+fn calls_through_variables() {
+    assert!(codes(
+        "double(n) {\n\tn * 2\n}\n\nmain() {\n\tlet offset = 1\n\tlet f = |n| n + offset\n\tprint(f(map([1], double)))\n}\n"
+    )
+    .is_empty());
+
+    assert_eq!(
+        codes("main() {\n\tlet f = |n, unused| n\n\tprint(f(1, 2))\n}\n"),
+        [Code::UnusedVariable]
+    );
+}
+
+#[test]
+fn cyclic_constants() {
+    assert!(codes("const A = B + 1\nconst B = 2\n\nmain() {\n\tprint(A)\n}\n").is_empty());
+
+    assert_eq!(
+        codes("const A = B\nconst B = f()\n\nf() {\n\tA\n}\n\nmain() {\n\tprint(A)\n}\n"),
+        [Code::CyclicConstant]
+    );
+}
+
+#[test]
+fn impure_constants() {
+    assert!(codes("const F = |x| x + 1\n\nmain() {\n\tprint(F(1))\n}\n").is_empty());
+
+    assert_eq!(
+        codes("const A = read_line()\nconst B = log(1)\n\nlog(n) {\n\tprint(n)\n\tn\n}\n\nmain() {\n\tprint(concat(A, \"!\"))\n\tprint(B)\n}\n"),
+        [Code::ImpureConstant, Code::ImpureConstant]
+    );
+}
+
+#[test]
+fn block_scopes() {
+    let shadowing = "main(n) {\n\tlet a = 1\n\tif n {\n\t\tlet a = 2\n\t\tprint(a)\n\t} else {\n\t\tprint(0)\n\t}\n\tprint(a)\n}\n";
+    let diagnostics = validate_source(shadowing);
+    assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+    assert_eq!(diagnostics[0].code, Code::ShadowedVariable);
+    assert_eq!(diagnostics[0].location.as_ref().unwrap().start.line, 4);
+
+    assert_eq!(
+        codes("main(n) {\n\tif n {\n\t\tlet a = 2\n\t\tprint(a)\n\t} else {\n\t\tprint(0)\n\t}\n\tprint(a)\n}\n"),
+        [Code::UnknownVariable]
+    );
+    assert_eq!(
+        codes("main() {\n\tlet a = 1\n\tprint(2)\n}\n"),
+        [Code::UnusedVariable]
+    );
+}